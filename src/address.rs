@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+use crate::query::canton_name;
+
+/// A Swiss address line such as `Lochbruggstrasse 39, 4242 Laufen` split
+/// into its parts.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Address {
+    pub street: Option<String>,
+    pub house_number: Option<String>,
+    /// The 4-digit Swiss postcode, or the 5-digit one of an enclave such as
+    /// Campione d'Italia.
+    pub postcode: Option<String>,
+    pub locality: Option<String>,
+    /// Canton abbreviation appended to ambiguous place names, as in
+    /// `Reinach BL`.
+    pub canton: Option<String>,
+}
+
+impl Address {
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        let (street_part, place_part) = match text.rsplit_once(',') {
+            Some((street, place)) => (Some(street.trim()), place.trim()),
+            None if starts_with_postcode(text) => (None, text),
+            None => (Some(text), ""),
+        };
+
+        let mut address = Self::default();
+
+        if let Some(street_part) = street_part.filter(|street| !street.is_empty()) {
+            match street_part.rsplit_once(' ') {
+                Some((street, number)) if is_house_number(number) => {
+                    address.street = Some(street.trim().to_owned());
+                    address.house_number = Some(number.to_owned());
+                }
+                _ => address.street = Some(street_part.to_owned()),
+            }
+        }
+
+        let mut place = place_part;
+        if let Some((postcode, rest)) = place.split_once(' ') {
+            if is_postcode(postcode) {
+                address.postcode = Some(postcode.to_owned());
+                place = rest.trim();
+            }
+        } else if is_postcode(place) {
+            address.postcode = Some(place.to_owned());
+            place = "";
+        }
+
+        if let Some((locality, canton)) = place.rsplit_once(' ') {
+            if canton.len() == 2
+                && canton.chars().all(|c| c.is_ascii_uppercase())
+                && canton_name(canton).is_some()
+            {
+                address.canton = Some(canton.to_owned());
+                place = locality.trim();
+            }
+        }
+
+        if !place.is_empty() {
+            address.locality = Some(place.to_owned());
+        }
+
+        address
+    }
+}
+
+fn is_postcode(word: &str) -> bool {
+    (4..=5).contains(&word.len()) && word.chars().all(|c| c.is_ascii_digit())
+}
+
+fn starts_with_postcode(text: &str) -> bool {
+    text.split_whitespace().next().is_some_and(is_postcode)
+}
+
+/// House numbers start with a digit and may carry a letter or a range, as in
+/// `39`, `4b` or `17-21`.
+fn is_house_number(word: &str) -> bool {
+    word.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && word
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '/')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(
+        street: Option<&str>,
+        house_number: Option<&str>,
+        postcode: &str,
+        locality: &str,
+        canton: Option<&str>,
+    ) -> Address {
+        Address {
+            street: street.map(str::to_owned),
+            house_number: house_number.map(str::to_owned),
+            postcode: Some(postcode.to_owned()),
+            locality: Some(locality.to_owned()),
+            canton: canton.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn parses_addresses_from_clinics_csv() {
+        let corpus = [
+            (
+                "Lochbruggstrasse 39, 4242 Laufen",
+                address(Some("Lochbruggstrasse"), Some("39"), "4242", "Laufen", None),
+            ),
+            (
+                "Chante-Merle 84, 2502 Biel/Bienne",
+                address(
+                    Some("Chante-Merle"),
+                    Some("84"),
+                    "2502",
+                    "Biel/Bienne",
+                    None,
+                ),
+            ),
+            (
+                "Rue Jean-Violette 3-5, 1205 Genève",
+                address(
+                    Some("Rue Jean-Violette"),
+                    Some("3-5"),
+                    "1205",
+                    "Genève",
+                    None,
+                ),
+            ),
+            (
+                "Spitalgasse 17-21, 3011 Bern",
+                address(Some("Spitalgasse"), Some("17-21"), "3011", "Bern", None),
+            ),
+            (
+                "Kantonsspital Bruderholz, 4101 Bruderholz",
+                address(
+                    Some("Kantonsspital Bruderholz"),
+                    None,
+                    "4101",
+                    "Bruderholz",
+                    None,
+                ),
+            ),
+            (
+                "St. Katharinental, 8253 Diessenhofen",
+                address(
+                    Some("St. Katharinental"),
+                    None,
+                    "8253",
+                    "Diessenhofen",
+                    None,
+                ),
+            ),
+            (
+                "9000 St. Gallen",
+                address(None, None, "9000", "St. Gallen", None),
+            ),
+            (
+                "1224 Chêne-Bougeries",
+                address(None, None, "1224", "Chêne-Bougeries", None),
+            ),
+            (
+                "Via Marconi 2, 22061 Campione d'Italia",
+                address(
+                    Some("Via Marconi"),
+                    Some("2"),
+                    "22061",
+                    "Campione d'Italia",
+                    None,
+                ),
+            ),
+            (
+                "En Chamard, 1442 Montagny-près-Yverdon",
+                address(
+                    Some("En Chamard"),
+                    None,
+                    "1442",
+                    "Montagny-près-Yverdon",
+                    None,
+                ),
+            ),
+        ];
+
+        for (text, expected) in corpus {
+            assert_eq!(Address::parse(text), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn separates_canton_suffix_from_locality() {
+        let parsed = Address::parse("Hauptstrasse 12, 4153 Reinach BL");
+        assert_eq!(parsed.locality.as_deref(), Some("Reinach"));
+        assert_eq!(parsed.canton.as_deref(), Some("BL"));
+
+        let parsed = Address::parse("Rue du Lac 1, 1025 St-Sulpice VD");
+        assert_eq!(parsed.locality.as_deref(), Some("St-Sulpice"));
+        assert_eq!(parsed.canton.as_deref(), Some("VD"));
+    }
+
+    #[test]
+    fn keeps_multi_word_localities() {
+        for locality in [
+            "La Chaux-de-Fonds",
+            "Affoltern am Albis",
+            "Matten b. Interlaken",
+            "Muraz (Collombey)",
+            "Sils/Segl Maria",
+        ] {
+            let parsed = Address::parse(&format!("Rue Neuve 5, 2300 {}", locality));
+            assert_eq!(parsed.locality.as_deref(), Some(locality));
+            assert_eq!(parsed.canton, None);
+        }
+    }
+
+    #[test]
+    fn every_address_in_clinics_csv_has_postcode_and_locality() {
+        let mut reader = csv::Reader::from_reader(include_str!("../clinics.csv").as_bytes());
+
+        for record in reader.records() {
+            let record = record.unwrap();
+            let parsed = Address::parse(&record[1]);
+            assert!(parsed.postcode.is_some(), "{}", &record[1]);
+            assert!(parsed.locality.is_some(), "{}", &record[1]);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use reqwest::header::{HeaderMap, ETAG, LAST_MODIFIED};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::checkpoint::fingerprint;
+use crate::ScrapeError;
+
+/// Fetched pages kept on disk, so extraction can be rerun without going
+/// back to the site. Every URL gets a `<hash>.html` file with the raw body
+/// and a `<hash>.json` file with its `CachedPage` metadata.
+#[derive(Debug, Clone)]
+pub struct PageCache {
+    dir: PathBuf,
+}
+
+/// What the cache knows about a page besides its body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedPage {
+    pub url: String,
+    /// When the body was last fetched or revalidated, as an HTTP date.
+    pub fetched_at: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl PageCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The cached metadata and body of `url`, if both are present. An entry
+    /// stored for another URL whose hash collides with this one's counts as
+    /// missing.
+    pub fn get(&self, url: &Url) -> Result<Option<(CachedPage, String)>, ScrapeError> {
+        let (meta_path, body_path) = self.paths(url);
+        if !meta_path.exists() || !body_path.exists() {
+            return Ok(None);
+        }
+
+        let meta: CachedPage = serde_json::from_str(&fs::read_to_string(meta_path)?)?;
+        if meta.url != url.as_str() {
+            return Ok(None);
+        }
+
+        let body = fs::read_to_string(body_path)?;
+        Ok(Some((meta, body)))
+    }
+
+    /// Stores `body` as fetched from `url` just now, with the validators
+    /// found in the response `headers`.
+    pub fn put(&self, url: &Url, headers: &HeaderMap, body: &str) -> Result<(), ScrapeError> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from)
+        };
+        let meta = CachedPage {
+            url: url.to_string(),
+            fetched_at: httpdate::fmt_http_date(SystemTime::now()),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        };
+
+        let (meta_path, body_path) = self.paths(url);
+        fs::create_dir_all(&self.dir)?;
+        fs::write(body_path, body)?;
+        fs::write(meta_path, serde_json::to_string_pretty(&meta)?)?;
+        Ok(())
+    }
+
+    /// Records that the cached copy of `url` was confirmed up to date.
+    pub fn touch(&self, url: &Url, mut meta: CachedPage) -> Result<(), ScrapeError> {
+        meta.fetched_at = httpdate::fmt_http_date(SystemTime::now());
+
+        let (meta_path, _) = self.paths(url);
+        fs::write(meta_path, serde_json::to_string_pretty(&meta)?)?;
+        Ok(())
+    }
+
+    fn paths(&self, url: &Url) -> (PathBuf, PathBuf) {
+        let key = format!("{:016x}", fingerprint(&[url.as_str()]));
+        (
+            self.dir.join(format!("{}.json", key)),
+            self.dir.join(format!("{}.html", key)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_of_a_colliding_url_is_not_served() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = PageCache::new(dir.path());
+        let stored: Url = "https://www.local.ch/en/q/Switzerland/clinique?page=1"
+            .parse()
+            .unwrap();
+        let other: Url = "https://www.local.ch/en/q/Switzerland/clinique?page=2"
+            .parse()
+            .unwrap();
+
+        cache
+            .put(&stored, &HeaderMap::new(), "<html></html>")
+            .unwrap();
+        assert_eq!(cache.get(&stored).unwrap().unwrap().1, "<html></html>");
+
+        // Place the entry where `other` is looked up, as a hash collision
+        // would.
+        let (stored_meta, stored_body) = cache.paths(&stored);
+        let (other_meta, other_body) = cache.paths(&other);
+        fs::copy(stored_meta, other_meta).unwrap();
+        fs::copy(stored_body, other_body).unwrap();
+
+        assert!(cache.get(&other).unwrap().is_none());
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{OutputFile, OutputOptions, ScrapeError};
+
+/// Progress of a crawl as saved to disk, so an interrupted run can pick up
+/// where it stopped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// The search URL the pages belong to, without the page parameter.
+    pub query: String,
+    /// Fingerprint of the settings that decide which pages exist.
+    pub config_hash: u64,
+    pub completed_pages: BTreeSet<i32>,
+    /// Pages that failed on their last attempt, with the error message.
+    pub failed_pages: BTreeMap<i32, String>,
+}
+
+impl Checkpoint {
+    pub fn new(query: String, config_hash: u64) -> Self {
+        Self {
+            query,
+            config_hash,
+            completed_pages: BTreeSet::new(),
+            failed_pages: BTreeMap::new(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScrapeError> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Saves the checkpoint by renaming a complete temporary file over the
+    /// old one, so a crash never leaves a half-written checkpoint behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ScrapeError> {
+        let options = OutputOptions {
+            append: false,
+            atomic: true,
+        };
+        let mut file = OutputFile::create(path, options)?;
+
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.commit()
+    }
+
+    pub fn is_completed(&self, page_num: i32) -> bool {
+        self.completed_pages.contains(&page_num)
+    }
+
+    pub fn record<T>(&mut self, page_num: i32, result: &Result<T, ScrapeError>) {
+        match result {
+            Ok(_) => {
+                self.failed_pages.remove(&page_num);
+                self.completed_pages.insert(page_num);
+            }
+            Err(err) => {
+                self.failed_pages.insert(page_num, err.to_string());
+            }
+        }
+    }
+}
+
+/// A checkpoint together with the file it is kept in.
+#[derive(Debug)]
+pub(crate) struct CheckpointFile {
+    pub path: PathBuf,
+    pub checkpoint: Checkpoint,
+}
+
+/// FNV-1a, used instead of `DefaultHasher` because checkpoint hashes have to
+/// stay the same across builds.
+pub(crate) fn fingerprint(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+
+    for part in parts {
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    hash
+}
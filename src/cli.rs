@@ -0,0 +1,381 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+use swiss_info_clinic_scraper::{
+    Category, Language, LocalCh, OutputFormat, PageCache, ScrapeError, ScraperConfig, SearchQuery,
+    Selectors, What, Where, DEFAULT_USER_AGENT, LOCAL_CH,
+};
+
+pub const DEFAULT_QUERY: &str = "clinique";
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Scrape business listings from local.ch")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Scrape all listing pages and write the results to a file
+    Scrape(ScrapeArgs),
+    /// Scrape a single listing page and print the results
+    Page(PageArgs),
+    /// Run every query of a TOML or YAML job file in one process
+    Batch(BatchArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SearchArgs {
+    /// Root URL of the directory site
+    #[arg(long, default_value = LOCAL_CH, value_parser = parse_base_url)]
+    pub site: String,
+
+    /// Full search URL the location and search term are appended to, e.g.
+    /// "https://www.local.ch/en/q", used instead of --site
+    #[arg(long, value_parser = parse_base_url, conflicts_with = "site")]
+    pub base_url: Option<String>,
+
+    /// Free-text search term, e.g. "clinique" [default: clinique]
+    #[arg(short, long, value_parser = parse_segment, conflicts_with = "category")]
+    pub query: Option<String>,
+
+    /// Business category, translated into the search language
+    #[arg(short, long, value_enum)]
+    pub category: Option<Category>,
+
+    /// Search in one canton, by its two-letter code
+    #[arg(long, value_parser = parse_canton, group = "where")]
+    pub canton: Option<Where>,
+
+    /// Search in one city or town
+    #[arg(long, value_parser = parse_segment, group = "where")]
+    pub city: Option<String>,
+
+    /// Search around a 4-digit postcode
+    #[arg(long, value_parser = parse_postcode, group = "where")]
+    pub postcode: Option<Where>,
+
+    /// Location path segment used as is, e.g. "Switzerland" or "Zurich"
+    #[arg(short, long, value_parser = parse_segment, group = "where")]
+    pub location: Option<String>,
+
+    /// Language the site answers in
+    #[arg(long, value_enum, default_value_t = Language::En)]
+    pub language: Language,
+
+    /// TOML file overriding the CSS selectors listing pages are read with
+    #[arg(long)]
+    pub selectors: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ClientArgs {
+    /// User-Agent header sent with every request
+    #[arg(long, default_value = DEFAULT_USER_AGENT)]
+    pub user_agent: String,
+
+    /// Proxy URL all requests are routed through
+    #[arg(long)]
+    pub proxy: Option<String>,
+
+    /// Extra header sent with every request, as "Name: value"
+    #[arg(long = "header", value_parser = parse_header)]
+    pub headers: Vec<(String, String)>,
+
+    /// Seconds to wait for a connection to be established
+    #[arg(long, default_value_t = 10)]
+    pub connect_timeout: u64,
+
+    /// Seconds to wait for a whole request to complete
+    #[arg(long, default_value_t = 30)]
+    pub timeout: u64,
+
+    /// Fetch pages even when the site's robots.txt disallows them
+    #[arg(long)]
+    pub ignore_robots_txt: bool,
+
+    /// Directory fetched pages are cached in, with their ETag and Last-Modified
+    #[arg(long)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Parse pages from the cache only, without network access
+    #[arg(long, requires = "cache_dir")]
+    pub offline: bool,
+}
+
+impl ClientArgs {
+    pub fn config(&self) -> ScraperConfig {
+        ScraperConfig {
+            connect_timeout: Duration::from_secs(self.connect_timeout),
+            timeout: Duration::from_secs(self.timeout),
+            user_agent: self.user_agent.clone(),
+            proxy: self.proxy.clone(),
+            headers: self.headers.clone(),
+            ..ScraperConfig::default()
+        }
+    }
+
+    pub fn cache(&self) -> Option<PageCache> {
+        self.cache_dir.as_ref().map(PageCache::new)
+    }
+}
+
+impl SearchArgs {
+    /// The search described by the arguments; without any, all of
+    /// Switzerland is searched for "clinique".
+    pub fn query(&self) -> SearchQuery {
+        let what = match (&self.query, self.category) {
+            (_, Some(category)) => What::Category(category),
+            (Some(query), None) => What::Text(query.clone()),
+            (None, None) => What::Text(DEFAULT_QUERY.to_owned()),
+        };
+
+        let location = match (&self.canton, &self.city, &self.postcode, &self.location) {
+            (Some(canton), _, _, _) => canton.clone(),
+            (_, Some(city), _, _) | (_, _, _, Some(city)) => Where::City(city.clone()),
+            (_, _, Some(postcode), _) => postcode.clone(),
+            _ => Where::Country,
+        };
+
+        SearchQuery::new(what, location).with_language(self.language)
+    }
+
+    /// The listing source to scrape, with the selector file applied.
+    pub fn source(&self) -> Result<LocalCh, ScrapeError> {
+        let query = self.query();
+        let source = match &self.base_url {
+            Some(base_url) => LocalCh::new(base_url.clone(), query.path()),
+            None => LocalCh::for_query(&self.site, &query),
+        };
+
+        match &self.selectors {
+            Some(path) => Ok(source.with_selectors(Selectors::load(path)?)),
+            None => Ok(source),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ScrapeArgs {
+    #[command(flatten)]
+    pub search: SearchArgs,
+
+    #[command(flatten)]
+    pub client: ClientArgs,
+
+    /// Upper bound on pages to scrape [default: until the last page]
+    #[arg(short = 'n', long, value_parser = clap::value_parser!(i32).range(1..))]
+    pub max_pages: Option<i32>,
+
+    /// Maximum number of pages fetched concurrently
+    #[arg(short, long, default_value_t = 10, value_parser = parse_positive)]
+    pub parallel: usize,
+
+    /// Fetch pages one after another instead of concurrently
+    #[arg(long, conflicts_with = "parallel")]
+    pub sequential: bool,
+
+    /// Maximum requests per second sent to the site
+    #[arg(long, value_parser = parse_rate)]
+    pub rate: Option<f64>,
+
+    /// Requests that may be sent back-to-back before --rate applies
+    #[arg(long, default_value_t = 1, requires = "rate", value_parser = clap::value_parser!(u32).range(1..))]
+    pub burst: u32,
+
+    /// Random pause of up to this many milliseconds before every request
+    #[arg(long, requires = "rate")]
+    pub politeness_delay_ms: Option<u64>,
+
+    /// Attempts per page before giving up on retryable errors
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_attempts: u32,
+
+    /// Initial retry delay in milliseconds, doubled after every attempt
+    #[arg(long, default_value_t = 500)]
+    pub retry_delay_ms: u64,
+
+    /// Fail the run when more than this fraction of pages (0.0 to 1.0)
+    /// fails; by default a single failed page fails it
+    #[arg(long, default_value_t = 0.0, value_parser = parse_ratio)]
+    pub max_failure_ratio: f64,
+
+    /// File the scraped records are written to
+    #[arg(short, long, default_value = "clinics.csv")]
+    pub output: PathBuf,
+
+    /// Add records to an existing output file instead of replacing it
+    #[arg(long)]
+    pub append: bool,
+
+    /// Write to a temporary file and rename it over the output when done.
+    /// No checkpoint is kept, since the output only exists once complete
+    #[arg(long, conflicts_with = "resume")]
+    pub atomic: bool,
+
+    /// Visit detail pages to find homepages missing from the result cards
+    #[arg(long)]
+    pub lookup_websites: bool,
+
+    /// Visit every detail page for opening hours, email, fax and more
+    #[arg(long)]
+    pub details: bool,
+
+    /// Keep duplicate entries instead of collapsing them
+    #[arg(long)]
+    pub no_dedup: bool,
+
+    /// Directory the HTML of pages that look like a changed layout is saved to
+    #[arg(long, default_value = "unparsed")]
+    pub unparsed_dir: PathBuf,
+
+    /// File tracking finished pages [default: <OUTPUT>.checkpoint.json]
+    #[arg(long)]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Skip pages the checkpoint lists as done and append to the output
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Output file format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Csv)]
+    pub format: OutputFormat,
+}
+
+impl ScrapeArgs {
+    pub fn checkpoint_path(&self) -> PathBuf {
+        self.checkpoint.clone().unwrap_or_else(|| {
+            let mut path = self.output.clone().into_os_string();
+            path.push(".checkpoint.json");
+            path.into()
+        })
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct PageArgs {
+    #[command(flatten)]
+    pub search: SearchArgs,
+
+    #[command(flatten)]
+    pub client: ClientArgs,
+
+    /// Listing page number to scrape
+    #[arg(short = 'n', long, default_value_t = 1, value_parser = clap::value_parser!(i32).range(1..))]
+    pub page: i32,
+
+    /// Directory the HTML of pages that look like a changed layout is saved to
+    #[arg(long, default_value = "unparsed")]
+    pub unparsed_dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct BatchArgs {
+    /// Job file listing the queries, with a .toml, .yaml or .yml extension
+    pub job: PathBuf,
+
+    /// TOML file overriding the CSS selectors listing pages are read with
+    #[arg(long)]
+    pub selectors: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub client: ClientArgs,
+
+    /// Pages fetched concurrently across all queries [default: from the job file]
+    #[arg(short, long, value_parser = parse_positive)]
+    pub parallel: Option<usize>,
+
+    /// Maximum requests per second sent to the site, across all queries
+    #[arg(long, value_parser = parse_rate)]
+    pub rate: Option<f64>,
+
+    /// Requests that may be sent back-to-back before --rate applies
+    #[arg(long, default_value_t = 1, requires = "rate", value_parser = clap::value_parser!(u32).range(1..))]
+    pub burst: u32,
+
+    /// Attempts per page before giving up on retryable errors
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_attempts: u32,
+
+    /// Fail the run when more than this fraction of any query's pages
+    /// (0.0 to 1.0) fails; by default a single failed page fails it
+    #[arg(long, default_value_t = 0.0, value_parser = parse_ratio)]
+    pub max_failure_ratio: f64,
+
+    /// Initial retry delay in milliseconds, doubled after every attempt
+    #[arg(long, default_value_t = 500)]
+    pub retry_delay_ms: u64,
+
+    /// Visit every detail page for opening hours, email, fax and more
+    #[arg(long)]
+    pub details: bool,
+
+    /// Keep duplicate entries instead of collapsing them per query
+    #[arg(long)]
+    pub no_dedup: bool,
+
+    /// Directory the HTML of pages that look like a changed layout is saved to
+    #[arg(long, default_value = "unparsed")]
+    pub unparsed_dir: PathBuf,
+}
+
+fn parse_base_url(value: &str) -> Result<String, String> {
+    if !(value.starts_with("http://") || value.starts_with("https://")) {
+        return Err("must start with http:// or https://".to_owned());
+    }
+
+    Ok(value.trim_end_matches('/').to_owned())
+}
+
+fn parse_canton(value: &str) -> Result<Where, String> {
+    Where::canton(value.trim()).map_err(|err| err.to_string())
+}
+
+fn parse_postcode(value: &str) -> Result<Where, String> {
+    Where::postcode(value.trim()).map_err(|err| err.to_string())
+}
+
+fn parse_segment(value: &str) -> Result<String, String> {
+    let value = value.trim().trim_matches('/');
+
+    if value.is_empty() {
+        return Err("must not be empty".to_owned());
+    }
+
+    Ok(value.to_owned())
+}
+
+fn parse_header(value: &str) -> Result<(String, String), String> {
+    match value.split_once(':') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_owned(), value.trim().to_owned()))
+        }
+        _ => Err("expected \"Name: value\"".to_owned()),
+    }
+}
+
+fn parse_ratio(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+        Ok(_) => Err("must be between 0.0 and 1.0".to_owned()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn parse_rate(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate > 0.0 && rate.is_finite() => Ok(rate),
+        Ok(_) => Err("must be a positive number".to_owned()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+fn parse_positive(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err("must be at least 1".to_owned()),
+        Ok(n) => Ok(n),
+        Err(err) => Err(err.to_string()),
+    }
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Address, ClinicDetails, PhoneNumber};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Clinic {
+    pub(crate) name: String,
+    pub(crate) address: String,
+    #[serde(default)]
+    pub(crate) street: Option<String>,
+    #[serde(default)]
+    pub(crate) house_number: Option<String>,
+    pub(crate) postcode: Option<String>,
+    pub(crate) city: Option<String>,
+    #[serde(default)]
+    pub(crate) canton: Option<String>,
+    /// The first phone number, in E.164 form.
+    pub(crate) phone: Option<String>,
+    #[serde(default)]
+    pub(crate) phones: Vec<PhoneNumber>,
+    /// The clinic's own homepage.
+    pub(crate) website: Option<String>,
+    /// The entry's page on the scraped directory site.
+    #[serde(default)]
+    pub(crate) detail_url: Option<String>,
+    /// Filled in when the scraper visits detail pages.
+    #[serde(default)]
+    pub(crate) details: Option<ClinicDetails>,
+    /// Name of the batch query that found the clinic.
+    #[serde(default)]
+    pub(crate) source_query: Option<String>,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+}
+
+impl Clinic {
+    /// A clinic with the given name and address line, which is split into
+    /// its parts with `Address::parse`. Listing sources fill in the rest
+    /// with the `with_*` methods.
+    pub fn new(name: impl Into<String>, address: impl Into<String>) -> Self {
+        let address = address.into();
+        let parsed = Address::parse(&address);
+
+        Self {
+            name: name.into(),
+            address,
+            street: None,
+            house_number: None,
+            postcode: None,
+            city: None,
+            canton: None,
+            phone: None,
+            phones: Vec::new(),
+            website: None,
+            detail_url: None,
+            details: None,
+            source_query: None,
+            tags: Vec::new(),
+        }
+        .with_address(parsed)
+    }
+
+    /// Replaces the address parts, e.g. when the site lists them
+    /// separately. The address line stays as it is.
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.street = address.street;
+        self.house_number = address.house_number;
+        self.postcode = address.postcode;
+        self.city = address.locality;
+        self.canton = address.canton;
+        self
+    }
+
+    /// Sets the phone numbers in the order shown; the first one becomes
+    /// `phone`.
+    pub fn with_phones(mut self, phones: Vec<PhoneNumber>) -> Self {
+        self.phone = phones.first().map(|phone| phone.e164.clone());
+        self.phones = phones;
+        self
+    }
+
+    pub fn with_website(mut self, website: impl Into<String>) -> Self {
+        self.website = Some(website.into());
+        self
+    }
+
+    pub fn with_detail_url(mut self, detail_url: impl Into<String>) -> Self {
+        self.detail_url = Some(detail_url.into());
+        self
+    }
+
+    pub fn with_details(mut self, details: ClinicDetails) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The full address line as shown on the listing card.
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn street(&self) -> Option<&str> {
+        self.street.as_deref()
+    }
+
+    pub fn house_number(&self) -> Option<&str> {
+        self.house_number.as_deref()
+    }
+
+    pub fn postcode(&self) -> Option<&str> {
+        self.postcode.as_deref()
+    }
+
+    pub fn city(&self) -> Option<&str> {
+        self.city.as_deref()
+    }
+
+    /// Canton abbreviation when the listing adds one to the city name.
+    pub fn canton(&self) -> Option<&str> {
+        self.canton.as_deref()
+    }
+
+    pub fn phone(&self) -> Option<&str> {
+        self.phone.as_deref()
+    }
+
+    /// Every phone number linked from the listing, in the order shown.
+    pub fn phones(&self) -> &[PhoneNumber] {
+        &self.phones
+    }
+
+    pub fn website(&self) -> Option<&str> {
+        self.website.as_deref()
+    }
+
+    pub fn detail_url(&self) -> Option<&str> {
+        self.detail_url.as_deref()
+    }
+
+    pub fn details(&self) -> Option<&ClinicDetails> {
+        self.details.as_ref()
+    }
+
+    pub fn source_query(&self) -> Option<&str> {
+        self.source_query.as_deref()
+    }
+
+    /// Tags of the batch query that found the clinic.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, Proxy};
+
+use crate::ScrapeError;
+
+pub const DEFAULT_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Settings for the HTTP client shared by every request a `Scraper` makes.
+#[derive(Debug, Clone)]
+pub struct ScraperConfig {
+    pub connect_timeout: Duration,
+    /// Limit for a whole request, from sending it to reading the last byte of
+    /// the body.
+    pub timeout: Duration,
+    pub user_agent: String,
+    pub proxy: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub gzip: bool,
+    pub brotli: bool,
+}
+
+impl Default for ScraperConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            proxy: None,
+            headers: Vec::new(),
+            gzip: true,
+            brotli: true,
+        }
+    }
+}
+
+impl ScraperConfig {
+    pub fn build_client(&self) -> Result<Client, ScrapeError> {
+        let mut headers = HeaderMap::new();
+
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|err| ScrapeError::Config(format!("header name {:?}: {}", name, err)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|err| ScrapeError::Config(format!("header {}: {}", name, err)))?;
+            headers.append(name, value);
+        }
+
+        let mut builder = Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.timeout)
+            .user_agent(&self.user_agent)
+            .default_headers(headers)
+            .gzip(self.gzip)
+            .brotli(self.brotli);
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy).map_err(ScrapeError::Client)?);
+        }
+
+        builder.build().map_err(ScrapeError::Client)
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicI32, AtomicUsize, Ordering};
+
+use reqwest::StatusCode;
+
+use crate::{ListingPage, ListingSource, ScrapeError};
+
+/// Pages in a row that may fail before an unbounded crawl gives up.
+const MAX_FAILURE_STREAK: usize = 5;
+
+/// Shared state of a crawl that decides which pages are still worth
+/// fetching as results come in, possibly out of order.
+#[derive(Debug)]
+pub(crate) struct CrawlProgress {
+    last_page: AtomicI32,
+    /// Whether `--max-pages` bounds the crawl, in which case every page up
+    /// to it is attempted however many fail.
+    bounded: bool,
+    failure_streak: AtomicUsize,
+}
+
+impl CrawlProgress {
+    pub fn new(max_pages: Option<i32>) -> Self {
+        Self {
+            last_page: AtomicI32::new(max_pages.unwrap_or(i32::MAX)),
+            bounded: max_pages.is_some(),
+            failure_streak: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn should_fetch(&self, page_num: i32) -> bool {
+        self.is_within(page_num)
+            && (self.bounded || self.failure_streak.load(Ordering::SeqCst) < MAX_FAILURE_STREAK)
+    }
+
+    /// Whether `page_num` is not past the last page known so far.
+    pub fn is_within(&self, page_num: i32) -> bool {
+        page_num <= self.last_page.load(Ordering::SeqCst)
+    }
+
+    pub fn update(
+        &self,
+        source: &dyn ListingSource,
+        page_num: i32,
+        result: &Result<ListingPage, ScrapeError>,
+    ) {
+        match last_page_hint(source, page_num, result) {
+            Some(last) => {
+                self.last_page.fetch_min(last, Ordering::SeqCst);
+            }
+            None if result.is_err() => {
+                self.failure_streak.fetch_add(1, Ordering::SeqCst);
+            }
+            None => self.failure_streak.store(0, Ordering::SeqCst),
+        }
+    }
+}
+
+/// The last page implied by the outcome of scraping `page_num`. Sites answer
+/// 404 for pages past the end, so that counts as a hint too, as does a page
+/// missing from the cache in an offline crawl.
+fn last_page_hint(
+    source: &dyn ListingSource,
+    page_num: i32,
+    result: &Result<ListingPage, ScrapeError>,
+) -> Option<i32> {
+    match result {
+        Ok(listing) => source.last_page(listing),
+        Err(ScrapeError::Status {
+            status: StatusCode::NOT_FOUND,
+            ..
+        }) if page_num > 1 => Some(page_num - 1),
+        Err(ScrapeError::NotCached { .. }) if page_num > 1 => Some(page_num - 1),
+        Err(_) => None,
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::Clinic;
+
+/// What a deduplication pass collapsed.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct DedupReport {
+    /// Clinics offered to the deduplicator, duplicates included.
+    pub total: usize,
+    pub duplicates: usize,
+    pub merged_fields: Vec<FieldMerge>,
+}
+
+/// A field of a kept clinic that was filled in from one of its duplicates.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldMerge {
+    pub clinic: String,
+    pub field: &'static str,
+}
+
+/// Collapses clinics that refer to the same entry. Clinics match on their
+/// local.ch detail URL, or failing that on their normalized name and
+/// address. Fields missing from the first copy are taken from later ones,
+/// except for seeded copies, which were already written by an earlier run.
+#[derive(Debug, Default)]
+pub struct Deduplicator {
+    clinics: Vec<Clinic>,
+    index: HashMap<String, usize>,
+    seeded: usize,
+    report: DedupReport,
+}
+
+impl Deduplicator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers clinics kept from an earlier run so that new copies of them
+    /// count as duplicates. Seeded clinics are not part of the report total.
+    pub fn seed(&mut self, clinics: impl IntoIterator<Item = Clinic>) {
+        for clinic in clinics {
+            self.insert_new(clinic);
+            self.seeded += 1;
+        }
+    }
+
+    /// Adds a clinic and returns whether it was new. A duplicate is merged
+    /// into the copy that was seen first.
+    pub fn insert(&mut self, clinic: Clinic) -> bool {
+        self.report.total += 1;
+
+        let existing = keys(&clinic)
+            .iter()
+            .find_map(|key| self.index.get(key).copied());
+
+        match existing {
+            Some(index) if index < self.seeded => {
+                self.report.duplicates += 1;
+                false
+            }
+            Some(index) => {
+                self.report.duplicates += 1;
+                let keys = keys(&clinic);
+                let kept = &mut self.clinics[index];
+                for field in merge(kept, clinic) {
+                    self.report.merged_fields.push(FieldMerge {
+                        clinic: kept.name.clone(),
+                        field,
+                    });
+                }
+                for key in keys {
+                    self.index.entry(key).or_insert(index);
+                }
+                false
+            }
+            None => {
+                self.insert_new(clinic);
+                true
+            }
+        }
+    }
+
+    pub fn report(&self) -> &DedupReport {
+        &self.report
+    }
+
+    /// The unique clinics inserted so far in the order they were first seen,
+    /// with the fields merged from their duplicates. Seeded clinics are left
+    /// out.
+    pub fn clinics(&self) -> &[Clinic] {
+        &self.clinics[self.seeded..]
+    }
+
+    /// The unique clinics in the order they were first seen, seeded ones
+    /// included.
+    pub fn into_parts(self) -> (Vec<Clinic>, DedupReport) {
+        (self.clinics, self.report)
+    }
+
+    fn insert_new(&mut self, clinic: Clinic) {
+        let index = self.clinics.len();
+
+        for key in keys(&clinic) {
+            self.index.entry(key).or_insert(index);
+        }
+        self.clinics.push(clinic);
+    }
+}
+
+/// Removes duplicate clinics, e.g. after `Scraper::scrape_pages_parallel`.
+pub fn dedup_clinics(clinics: Vec<Clinic>) -> (Vec<Clinic>, DedupReport) {
+    let mut deduplicator = Deduplicator::new();
+
+    for clinic in clinics {
+        deduplicator.insert(clinic);
+    }
+
+    deduplicator.into_parts()
+}
+
+fn keys(clinic: &Clinic) -> Vec<String> {
+    let mut keys = Vec::with_capacity(2);
+
+    if let Some(url) = &clinic.detail_url {
+        let url = url.split(['?', '#']).next().unwrap_or_default();
+        keys.push(format!("url:{}", url.trim_end_matches('/').to_lowercase()));
+    }
+
+    keys.push(format!(
+        "name:{}|{}",
+        normalize(&clinic.name),
+        normalize(&clinic.address)
+    ));
+
+    keys
+}
+
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Fills the empty fields of `kept` from `duplicate` and returns their names.
+fn merge(kept: &mut Clinic, duplicate: Clinic) -> Vec<&'static str> {
+    let mut merged = Vec::new();
+
+    if kept.phones.is_empty() && !duplicate.phones.is_empty() {
+        kept.phones = duplicate.phones;
+        merged.push("phones");
+    }
+
+    if kept.details.is_none() && duplicate.details.is_some() {
+        kept.details = duplicate.details;
+        merged.push("details");
+    }
+
+    let fields = [
+        ("street", &mut kept.street, duplicate.street),
+        (
+            "house_number",
+            &mut kept.house_number,
+            duplicate.house_number,
+        ),
+        ("postcode", &mut kept.postcode, duplicate.postcode),
+        ("city", &mut kept.city, duplicate.city),
+        ("canton", &mut kept.canton, duplicate.canton),
+        ("phone", &mut kept.phone, duplicate.phone),
+        ("website", &mut kept.website, duplicate.website),
+        ("detail_url", &mut kept.detail_url, duplicate.detail_url),
+    ];
+
+    for (name, kept, duplicate) in fields {
+        if kept.is_none() && duplicate.is_some() {
+            *kept = duplicate;
+            merged.push(name);
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PhoneNumber;
+
+    fn clinic(name: &str, detail_url: Option<&str>, phone: Option<&str>) -> Clinic {
+        let mut clinic = Clinic::new(name, "Witellikerstrasse 40, 8032 Zürich");
+        if let Some(detail_url) = detail_url {
+            clinic = clinic.with_detail_url(detail_url);
+        }
+        if let Some(phone) = phone {
+            clinic = clinic.with_phones(vec![PhoneNumber::parse(phone).unwrap()]);
+        }
+        clinic
+    }
+
+    #[test]
+    fn keys_ignore_query_case_and_punctuation() {
+        let listed = clinic(
+            "Klinik Hirslanden",
+            Some("https://www.local.ch/en/d/zuerich/8032/klinik-AbC123/?rid=1"),
+            None,
+        );
+        let linked = clinic(
+            "KLINIK  HIRSLANDEN!",
+            Some("https://www.local.ch/en/d/zuerich/8032/Klinik-abc123#map"),
+            None,
+        );
+
+        assert_eq!(keys(&listed), keys(&linked));
+        assert_eq!(
+            keys(&clinic("Klinik Hirslanden", None, None)),
+            ["name:klinik hirslanden|witellikerstrasse 40 8032 zürich"]
+        );
+    }
+
+    #[test]
+    fn merge_fills_only_missing_fields() {
+        let mut kept = clinic("Klinik Hirslanden", None, Some("+41443873000"));
+        let duplicate = clinic(
+            "Klinik Hirslanden",
+            Some("https://www.local.ch/en/d/zuerich/8032/klinik-AbC123"),
+            Some("+41443873111"),
+        );
+
+        assert_eq!(merge(&mut kept, duplicate), ["detail_url"]);
+        assert_eq!(kept.phone(), Some("+41443873000"));
+        assert_eq!(
+            kept.detail_url(),
+            Some("https://www.local.ch/en/d/zuerich/8032/klinik-AbC123")
+        );
+    }
+
+    #[test]
+    fn dedup_clinics_keeps_first_copies_and_reports_merges() {
+        let url = "https://www.local.ch/en/d/zuerich/8032/klinik-AbC123";
+        let (clinics, report) = dedup_clinics(vec![
+            clinic("Klinik Hirslanden", Some(url), None),
+            clinic("Clinique des Grangettes", None, None),
+            clinic("Hirslanden Klinik", Some(url), Some("+41443873000")),
+            clinic("Clinique des Grangettes", None, None),
+        ]);
+
+        let names = clinics.iter().map(Clinic::name).collect::<Vec<_>>();
+        assert_eq!(names, ["Klinik Hirslanden", "Clinique des Grangettes"]);
+        assert_eq!(clinics[0].phone(), Some("+41443873000"));
+        assert_eq!(report.total, 4);
+        assert_eq!(report.duplicates, 2);
+        let merged = report
+            .merged_fields
+            .iter()
+            .map(|merge| (merge.clinic.as_str(), merge.field))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            merged,
+            [
+                ("Klinik Hirslanden", "phones"),
+                ("Klinik Hirslanden", "phone")
+            ]
+        );
+    }
+
+    #[test]
+    fn seeded_clinics_are_not_merged_into() {
+        let mut deduplicator = Deduplicator::new();
+        deduplicator.seed([clinic("Klinik Hirslanden", None, None)]);
+
+        assert!(!deduplicator.insert(clinic("Klinik Hirslanden", None, Some("+41443873000"))));
+        assert!(deduplicator.insert(clinic("Clinique des Grangettes", None, None)));
+
+        assert!(deduplicator.report().merged_fields.is_empty());
+        assert_eq!(deduplicator.report().duplicates, 1);
+        let names = deduplicator
+            .clinics()
+            .iter()
+            .map(Clinic::name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Clinique des Grangettes"]);
+    }
+}
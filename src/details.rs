@@ -0,0 +1,182 @@
+use reqwest::Url;
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Class, Name, Predicate};
+use serde::{Deserialize, Serialize};
+
+use crate::links::{external_homepage, is_same_site, resolve};
+use crate::PhoneNumber;
+
+/// Everything a directory entry's detail page adds to its search result
+/// card.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClinicDetails {
+    pub opening_hours: Vec<OpeningHours>,
+    pub email: Option<String>,
+    pub fax: Option<PhoneNumber>,
+    /// Phone numbers besides the fax, in the order shown.
+    pub phones: Vec<PhoneNumber>,
+    pub categories: Vec<String>,
+    pub languages: Vec<String>,
+    pub description: Option<String>,
+    pub social_links: Vec<String>,
+    pub website: Option<String>,
+}
+
+/// One row of an opening hours table, e.g. `Mon` / `08:00 - 12:00, 13:30 - 17:00`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OpeningHours {
+    pub days: String,
+    pub hours: String,
+}
+
+impl ClinicDetails {
+    /// Parses a detail page fetched from `url`, which relative links are
+    /// resolved against.
+    pub fn parse(html: &str, url: &Url) -> Self {
+        let document = Document::from(html);
+        let mut details = Self::default();
+
+        for row in document.find(Class("opening-hours").descendant(Name("tr"))) {
+            let cells = row
+                .find(Name("th").or(Name("td")))
+                .map(|cell| squash(&cell.text()))
+                .collect::<Vec<_>>();
+            if let Some((days, hours)) = cells.split_first() {
+                details.opening_hours.push(OpeningHours {
+                    days: days.clone(),
+                    hours: hours.join(", "),
+                });
+            }
+        }
+
+        for link in document.find(Name("a")) {
+            let Some(href) = link.attr("href") else {
+                continue;
+            };
+
+            if let Some(email) = href.strip_prefix("mailto:") {
+                if details.email.is_none() {
+                    details.email = Some(email.split('?').next().unwrap_or_default().to_owned());
+                }
+            } else if href.starts_with("tel:") {
+                let Some(phone) = PhoneNumber::parse(href) else {
+                    continue;
+                };
+                if is_fax(&link) {
+                    details.fax.get_or_insert(phone);
+                } else if !details.phones.contains(&phone) {
+                    details.phones.push(phone);
+                }
+            } else if let Some(link) = resolve(url, href) {
+                if !is_same_site(&link, url)
+                    && is_social(&link)
+                    && !details.social_links.contains(&link.to_string())
+                {
+                    details.social_links.push(link.to_string());
+                }
+            }
+        }
+
+        details.categories = texts(&document, "detail-categories");
+        details.languages = texts(&document, "detail-languages");
+
+        details.description = document
+            .find(Class("detail-description"))
+            .next()
+            .map(|node| squash(&node.text()))
+            .or_else(|| {
+                document
+                    .find(Name("meta").and(Attr("name", "description")))
+                    .next()
+                    .and_then(|node| node.attr("content"))
+                    .map(squash)
+            })
+            .filter(|description| !description.is_empty());
+
+        let hrefs = document.find(Name("a")).filter_map(|n| n.attr("href"));
+        details.website = external_homepage(url, hrefs).map(String::from);
+
+        details
+    }
+}
+
+/// Collapses runs of whitespace, which the site's markup is full of.
+fn squash(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The texts of the list items below every element with `class`.
+fn texts(document: &Document, class: &str) -> Vec<String> {
+    let mut texts = Vec::new();
+
+    for item in document.find(Class(class).descendant(Name("li").or(Name("a")))) {
+        let text = squash(&item.text());
+        if !text.is_empty() && !texts.contains(&text) {
+            texts.push(text);
+        }
+    }
+
+    texts
+}
+
+/// A `tel:` link is a fax number when its own text, `title` or
+/// `aria-label` says so, or the label right before it does. Labels are
+/// looked for next to the link, or next to its parent when the link has
+/// nothing before it, as in `<dt>Fax</dt><dd><a href="tel:…">`. The text of
+/// a container shared with other numbers is not a label.
+fn is_fax(link: &Node) -> bool {
+    let says_fax = |text: &str| text.to_lowercase().contains("fax");
+
+    if says_fax(&link.text())
+        || ["title", "aria-label"]
+            .iter()
+            .any(|name| link.attr(name).is_some_and(says_fax))
+    {
+        return true;
+    }
+
+    let label = match preceding_label(link) {
+        Some(label) => Some(label),
+        None if link.prev().is_none_or(|prev| is_blank(&prev)) => {
+            link.parent().and_then(|parent| preceding_label(&parent))
+        }
+        None => None,
+    };
+
+    label.is_some_and(|label| says_fax(&label))
+}
+
+/// The text of the nearest non-blank sibling before `node`, unless that is
+/// another link.
+fn preceding_label(node: &Node) -> Option<String> {
+    let mut prev = node.prev();
+
+    while let Some(sibling) = prev {
+        if !is_blank(&sibling) {
+            return (sibling.name() != Some("a")).then(|| sibling.text());
+        }
+        prev = sibling.prev();
+    }
+
+    None
+}
+
+fn is_blank(node: &Node) -> bool {
+    node.as_text().is_some_and(|text| text.trim().is_empty())
+}
+
+fn is_social(url: &Url) -> bool {
+    let host = url.host_str().unwrap_or_default();
+
+    [
+        "facebook.com",
+        "instagram.com",
+        "twitter.com",
+        "x.com",
+        "linkedin.com",
+        "youtube.com",
+    ]
+    .iter()
+    .any(|social| host == *social || host.ends_with(&format!(".{}", social)))
+}
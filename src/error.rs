@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScrapeError {
+    #[error("request for page {page} failed: {source}")]
+    Network {
+        page: i32,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("page {page} returned HTTP status {status}")]
+    Status {
+        page: i32,
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+
+    #[error("robots.txt disallows fetching page {page} ({url}); pass an override to ignore it")]
+    Disallowed { page: i32, url: String },
+
+    #[error("robots.txt {url} could not be fetched for page {page}: {source}")]
+    RobotsUnavailable {
+        page: i32,
+        url: String,
+        #[source]
+        source: Box<ScrapeError>,
+    },
+
+    #[error("card {card} on page {page} is missing its {field}")]
+    MissingField {
+        page: i32,
+        card: usize,
+        field: &'static str,
+    },
+
+    #[error("page {page} ({url}) has content but no recognizable results; the site's markup may have changed")]
+    MarkupChanged {
+        page: i32,
+        url: String,
+        /// Where the page's HTML was saved for inspection, if it was.
+        saved: Option<PathBuf>,
+    },
+
+    #[error("page {page} ({url}) is not in the cache")]
+    NotCached { page: i32, url: String },
+
+    #[error("{failed} of {attempted} pages failed")]
+    TooManyFailures { failed: usize, attempted: usize },
+
+    #[error("invalid scraper configuration: {0}")]
+    Config(String),
+
+    #[error("failed to build HTTP client: {0}")]
+    Client(#[source] reqwest::Error),
+
+    #[error("failed to write CSV: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("failed to write JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl ScrapeError {
+    /// The listing page the error occurred on, if it is tied to one.
+    pub fn page(&self) -> Option<i32> {
+        match self {
+            Self::Network { page, .. }
+            | Self::Status { page, .. }
+            | Self::Disallowed { page, .. }
+            | Self::RobotsUnavailable { page, .. }
+            | Self::MissingField { page, .. }
+            | Self::MarkupChanged { page, .. }
+            | Self::NotCached { page, .. } => Some(*page),
+            Self::TooManyFailures { .. }
+            | Self::Config(_)
+            | Self::Client(_)
+            | Self::Csv(_)
+            | Self::Json(_)
+            | Self::Io(_) => None,
+        }
+    }
+
+    /// The HTTP status the server answered with, if one was received.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Network { source, .. } => source.status(),
+            Self::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{Category, Language, OutputFormat, ScrapeError, SearchQuery, What, Where, LOCAL_CH};
+
+/// A batch of searches run by one process, read from a TOML or YAML file:
+///
+/// ```toml
+/// concurrency = 8
+/// merge = "all.csv"
+///
+/// [[queries]]
+/// name = "dentists-zh"
+/// category = "dentist"
+/// canton = "ZH"
+/// max_pages = 5
+/// tags = ["dental"]
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobFile {
+    #[serde(default = "default_site")]
+    pub site: String,
+    /// Listing pages in flight at once, across all queries.
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    #[serde(default)]
+    pub format: OutputFormat,
+    /// Writes every query's results to this one file, with a `source_query`
+    /// column, instead of to each query's own output.
+    #[serde(default)]
+    pub merge: Option<PathBuf>,
+    /// Page limit for queries that do not set their own.
+    #[serde(default)]
+    pub max_pages: Option<i32>,
+    pub queries: Vec<JobQuery>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobQuery {
+    /// Identifies the query in logs and in the `source_query` column;
+    /// defaults to the search path.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub query: Option<String>,
+    #[serde(default)]
+    pub category: Option<Category>,
+    #[serde(default)]
+    pub canton: Option<String>,
+    #[serde(default)]
+    pub city: Option<String>,
+    #[serde(default)]
+    pub postcode: Option<String>,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub max_pages: Option<i32>,
+    #[serde(default)]
+    pub output: Option<PathBuf>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_site() -> String {
+    LOCAL_CH.to_owned()
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+impl JobFile {
+    /// Reads a job file, choosing the syntax by its `.toml`, `.yaml` or
+    /// `.yml` extension, and checks every query before anything is fetched.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScrapeError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let invalid = |err: &dyn std::fmt::Display| {
+            ScrapeError::Config(format!("invalid job file {}: {}", path.display(), err))
+        };
+
+        let job: Self = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&text).map_err(|err| invalid(&err))?,
+            Some("yaml" | "yml") => serde_yaml::from_str(&text).map_err(|err| invalid(&err))?,
+            _ => return Err(invalid(&"expected a .toml, .yaml or .yml file")),
+        };
+
+        job.validate().map_err(|err| invalid(&err))?;
+        Ok(job)
+    }
+
+    fn validate(&self) -> Result<(), ScrapeError> {
+        if self.queries.is_empty() {
+            return Err(ScrapeError::Config("no queries".to_owned()));
+        }
+        if self.concurrency == 0 {
+            return Err(ScrapeError::Config(
+                "concurrency must be at least 1".to_owned(),
+            ));
+        }
+
+        let mut names = HashSet::new();
+        let mut outputs = HashSet::new();
+        if let Some(merge) = &self.merge {
+            outputs.insert(normalize(merge));
+        }
+
+        for query in &self.queries {
+            let search = query.search_query()?;
+            let name = query.name(&search);
+
+            if !names.insert(name.clone()) {
+                return Err(ScrapeError::Config(format!("duplicate query {:?}", name)));
+            }
+            if self.merge.is_none() && query.output.is_none() {
+                return Err(ScrapeError::Config(format!(
+                    "query {:?} has no output and the job does not merge",
+                    name
+                )));
+            }
+            if let Some(output) = &query.output {
+                if !outputs.insert(normalize(output)) {
+                    return Err(ScrapeError::Config(format!(
+                        "query {:?} writes to {}, which another output already uses",
+                        name,
+                        output.display()
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The page limit that applies to `query`.
+    pub fn max_pages(&self, query: &JobQuery) -> Option<i32> {
+        query.max_pages.or(self.max_pages)
+    }
+}
+
+/// `path` without `.` components, so that `out.csv` and `./out.csv` compare
+/// equal.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+impl JobQuery {
+    pub fn search_query(&self) -> Result<SearchQuery, ScrapeError> {
+        let what = match (&self.query, self.category) {
+            (Some(_), Some(_)) => {
+                return Err(ScrapeError::Config(
+                    "a query takes either a query text or a category, not both".to_owned(),
+                ))
+            }
+            (Some(text), None) => What::Text(text.clone()),
+            (None, Some(category)) => What::Category(category),
+            (None, None) => {
+                return Err(ScrapeError::Config(
+                    "a query needs a query text or a category".to_owned(),
+                ))
+            }
+        };
+
+        let location = match (&self.canton, &self.city, &self.postcode) {
+            (None, None, None) => Where::Country,
+            (Some(canton), None, None) => Where::canton(canton)?,
+            (None, Some(city), None) => Where::City(city.clone()),
+            (None, None, Some(postcode)) => Where::postcode(postcode)?,
+            _ => {
+                return Err(ScrapeError::Config(
+                    "a query takes at most one of canton, city and postcode".to_owned(),
+                ))
+            }
+        };
+
+        Ok(SearchQuery::new(what, location).with_language(self.language))
+    }
+
+    /// The query's `name`, or the path of `search` when it has none.
+    pub fn name(&self, search: &SearchQuery) -> String {
+        self.name.clone().unwrap_or_else(|| search.path())
+    }
+}
@@ -1,159 +1,626 @@
-use csv::Writer;
-use futures::future::join_all;
-use reqwest::Client;
+mod address;
+mod cache;
+mod checkpoint;
+mod clinic;
+mod config;
+mod crawl;
+mod dedup;
+mod details;
+mod error;
+mod job;
+mod links;
+mod listing;
+mod output;
+mod phone;
+mod query;
+mod rate_limit;
+mod report;
+mod retry;
+mod robots;
+mod selector;
+mod source;
+
+use futures::{future, stream, Stream, StreamExt};
+use reqwest::header::{IF_MODIFIED_SINCE, IF_NONE_MATCH};
+use reqwest::{Client, StatusCode, Url};
 use select::document::Document;
-use select::predicate::{Class, Name, Predicate};
-use std::error::Error;
-use std::fs::File;
-use std::sync::Arc;
+use select::predicate::Name;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tokio::sync::Semaphore;
 
-#[derive(Debug)]
-pub struct Clinic {
-    name: String,
-    address: String,
-    postcode: Option<String>,
-    city: Option<String>,
-    phone: Option<String>,
-    website: Option<String>,
-}
+use crate::checkpoint::{fingerprint, CheckpointFile};
+use crate::crawl::CrawlProgress;
+use crate::links::external_homepage;
+use crate::rate_limit::RateLimiter;
+use crate::retry::parse_retry_after;
+use crate::robots::RobotsCache;
+
+pub use address::Address;
+pub use cache::{CachedPage, PageCache};
+pub use checkpoint::Checkpoint;
+pub use clinic::Clinic;
+pub use config::{ScraperConfig, DEFAULT_USER_AGENT};
+pub use dedup::{dedup_clinics, DedupReport, Deduplicator, FieldMerge};
+pub use details::{ClinicDetails, OpeningHours};
+pub use error::ScrapeError;
+pub use job::{JobFile, JobQuery};
+pub use listing::ListingPage;
+pub use output::{
+    write_csv, write_json, write_jsonl, write_to_csv, write_to_csv_with, write_to_json,
+    write_to_jsonl, OutputFile, OutputFormat, OutputOptions, StreamingWriter,
+};
+pub use phone::{PhoneKind, PhoneNumber};
+pub use query::{Category, Language, SearchQuery, What, Where, LOCAL_CH};
+pub use rate_limit::RateLimit;
+pub use report::{PageFailure, ScrapeReport};
+pub use retry::RetryPolicy;
+pub use robots::RobotsRules;
+pub use selector::{Selector, Selectors};
+pub use source::{ListingSource, LocalCh};
 
 pub struct Scraper {
-    base_url: String,
-    query: String,
-    max_pages: i32,
+    source: Box<dyn ListingSource>,
+    client: Client,
+    user_agent: String,
+    max_pages: Option<i32>,
+    max_parallel: usize,
+    max_failure_ratio: Option<f64>,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    robots: Arc<RobotsCache>,
+    ignore_robots_txt: bool,
+    checkpoint: Option<Mutex<CheckpointFile>>,
+    lookup_websites: bool,
+    fetch_details: bool,
+    label: Option<(String, Vec<String>)>,
+    unparsed_dir: Option<PathBuf>,
+    cache: Option<PageCache>,
+    offline: bool,
     semaphore: Arc<Semaphore>,
 }
 
 impl Scraper {
-    pub fn new(base_url: String, query: String, max_pages: i32, max_parallel: usize) -> Self {
+    /// Creates a local.ch scraper that follows the listing until its last
+    /// page, or until `max_pages` when an upper bound is given.
+    pub fn new(
+        base_url: String,
+        query: String,
+        max_pages: Option<i32>,
+        max_parallel: usize,
+    ) -> Self {
+        Self::from_source(LocalCh::new(base_url, query), max_pages, max_parallel)
+    }
+
+    /// Creates a scraper for a typed search on `site`, usually `LOCAL_CH`.
+    pub fn for_query(
+        site: &str,
+        query: &SearchQuery,
+        max_pages: Option<i32>,
+        max_parallel: usize,
+    ) -> Self {
+        Self::from_source(LocalCh::for_query(site, query), max_pages, max_parallel)
+    }
+
+    /// Creates a scraper that pages through any `ListingSource`.
+    pub fn from_source(
+        source: impl ListingSource + 'static,
+        max_pages: Option<i32>,
+        max_parallel: usize,
+    ) -> Self {
         Self {
-            base_url,
-            query,
+            source: Box::new(source),
+            client: ScraperConfig::default()
+                .build_client()
+                .expect("default scraper config builds a client"),
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
             max_pages,
+            max_parallel,
+            max_failure_ratio: None,
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: None,
+            robots: Arc::new(RobotsCache::default()),
+            ignore_robots_txt: false,
+            checkpoint: None,
+            lookup_websites: false,
+            fetch_details: false,
+            label: None,
+            unparsed_dir: None,
+            cache: None,
+            offline: false,
             semaphore: Arc::new(Semaphore::new(max_parallel)),
         }
     }
 
-    pub async fn scrape_pages_parallel(&self) -> Result<Vec<Clinic>, Box<dyn Error>> {
-        let mut clinics = Vec::new();
-        let pages = (1..=self.max_pages).collect::<Vec<_>>();
-        let semaphore = self.semaphore.clone();
-        let scraped_pages = pages.iter().map(|page_num| {
-            let semaphore = semaphore.clone();
-            async move {
-                let guard = semaphore.acquire().await;
-                let results = self.scrape_page(*page_num).await;
-                drop(guard);
-                results
-            }
-        });
+    /// Replaces the HTTP client with one built from `config`.
+    pub fn with_config(mut self, config: &ScraperConfig) -> Result<Self, ScrapeError> {
+        self.client = config.build_client()?;
+        self.user_agent = config.user_agent.clone();
+        Ok(self)
+    }
 
-        let results = join_all(scraped_pages).await;
+    /// Fails a multi-page scrape when more than `ratio` (0.0 to 1.0) of the
+    /// attempted pages could not be fetched.
+    pub fn with_max_failure_ratio(mut self, ratio: f64) -> Self {
+        self.max_failure_ratio = Some(ratio);
+        self
+    }
 
-        for result in results {
-            if let Ok(page_clinics) = result {
-                clinics.extend(page_clinics)
-            }
-        }
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
 
-        Ok(clinics)
+    /// Throttles every request, sequential or parallel, to `rate_limit`.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(rate_limit)));
+        self
     }
 
-    pub async fn scrape_pages(&self) -> Result<Vec<Clinic>, Box<dyn Error>> {
-        let mut clinics = Vec::new();
-        let pages = (1..=self.max_pages).collect::<Vec<_>>();
+    /// Uses the HTTP client, rate limit and robots.txt cache of `other`, and
+    /// draws on its concurrency budget, so that several scrapers together
+    /// never have more than `other`'s `max_parallel` pages in flight.
+    pub fn share_with(mut self, other: &Scraper) -> Self {
+        self.client = other.client.clone();
+        self.user_agent = other.user_agent.clone();
+        self.rate_limiter = other.rate_limiter.clone();
+        self.robots = other.robots.clone();
+        self.semaphore = other.semaphore.clone();
+        self.cache = other.cache.clone();
+        self.offline = other.offline;
+        self
+    }
 
-        for page_num in pages {
-            let page_clinics = self.scrape_page(page_num).await?;
-            clinics.extend(page_clinics);
-        }
+    /// Labels every scraped clinic with the name and tags of the query that
+    /// found it.
+    pub fn with_label(mut self, name: impl Into<String>, tags: Vec<String>) -> Self {
+        self.label = Some((name.into(), tags));
+        self
+    }
 
-        Ok(clinics)
+    /// Fetches pages even when the host's robots.txt disallows them.
+    pub fn ignore_robots_txt(mut self) -> Self {
+        self.ignore_robots_txt = true;
+        self
     }
 
-    pub async fn scrape_page(&self, page_num: i32) -> Result<Vec<Clinic>, Box<dyn Error>> {
-        println!("Scraping page {}.", page_num);
+    /// Visits the detail page of every clinic whose card has no link to its
+    /// own homepage and takes the homepage from there.
+    pub fn with_website_lookup(mut self) -> Self {
+        self.lookup_websites = true;
+        self
+    }
+
+    /// Follows every clinic's detail URL and attaches the `ClinicDetails`
+    /// found there. Detail pages go through the same retry policy, rate
+    /// limit and robots.txt checks as listing pages, and are fetched one by
+    /// one within each listing page's concurrency slot.
+    pub fn with_details(mut self) -> Self {
+        self.fetch_details = true;
+        self
+    }
 
-        let client = Client::new();
-        let page_url = format!("{}{}?page={}", self.base_url, self.query, page_num);
-        let res = client.get(&page_url).send().await?;
+    /// Keeps every fetched page in `cache` and revalidates cached pages with
+    /// their ETag or Last-Modified date instead of downloading them again.
+    pub fn with_cache(mut self, cache: PageCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
 
-        if !res.status().is_success() {
+    /// Reads pages from `cache` only, without any network access. Pages
+    /// missing from the cache fail with `ScrapeError::NotCached`.
+    pub fn offline(mut self, cache: PageCache) -> Self {
+        self.cache = Some(cache);
+        self.offline = true;
+        self
+    }
+
+    /// Saves the HTML of every page that looks like a changed layout, see
+    /// `ScrapeError::MarkupChanged`, to a file in `dir`.
+    pub fn with_unparsed_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.unparsed_dir = Some(dir.into());
+        self
+    }
+
+    /// Keeps a checkpoint of finished pages at `path`. With `resume` set, an
+    /// existing checkpoint is loaded and its completed pages are skipped; it
+    /// must have been written for the same query and page limit.
+    pub fn with_checkpoint(
+        mut self,
+        path: impl Into<PathBuf>,
+        resume: bool,
+    ) -> Result<Self, ScrapeError> {
+        let path = path.into();
+        let query = self.source.key();
+        let max_pages = self
+            .max_pages
+            .map(|max| max.to_string())
+            .unwrap_or_default();
+        let config_hash = fingerprint(&[&query, &max_pages]);
+
+        let checkpoint = if resume && path.exists() {
+            let checkpoint = Checkpoint::load(&path)?;
+            if checkpoint.query != query || checkpoint.config_hash != config_hash {
+                return Err(ScrapeError::Config(format!(
+                    "checkpoint {} was written for a different query or page limit ({})",
+                    path.display(),
+                    checkpoint.query
+                )));
+            }
             println!(
-                "Failed to fetch page {}. Response status: {:?}",
-                page_num,
-                res.status()
+                "Resuming from {} with {} completed pages.",
+                path.display(),
+                checkpoint.completed_pages.len()
             );
-            return Ok(vec![]);
+            checkpoint
+        } else {
+            Checkpoint::new(query, config_hash)
+        };
+
+        self.checkpoint = Some(Mutex::new(CheckpointFile { path, checkpoint }));
+        Ok(self)
+    }
+
+    /// Marks a page as done in the checkpoint and saves it. Consumers of
+    /// `stream_pages` call this once the page's records have been written;
+    /// `scrape_pages` and `scrape_pages_parallel` do it themselves.
+    pub fn checkpoint_page<T>(
+        &self,
+        page_num: i32,
+        result: &Result<T, ScrapeError>,
+    ) -> Result<(), ScrapeError> {
+        let Some(checkpoint) = &self.checkpoint else {
+            return Ok(());
+        };
+
+        let mut file = checkpoint.lock().unwrap();
+        file.checkpoint.record(page_num, result);
+        file.checkpoint.save(&file.path)
+    }
+
+    pub async fn scrape_pages_parallel(&self) -> Result<ScrapeReport, ScrapeError> {
+        self.collect_report(self.max_parallel).await
+    }
+
+    pub async fn scrape_pages(&self) -> Result<ScrapeReport, ScrapeError> {
+        self.collect_report(1).await
+    }
+
+    /// Yields each page's clinics as soon as the page has been scraped, in
+    /// completion order rather than page order.
+    pub fn stream_pages(&self) -> impl Stream<Item = (i32, Result<Vec<Clinic>, ScrapeError>)> + '_ {
+        self.listing_stream(self.max_parallel)
+            .map(|(page_num, result)| (page_num, result.map(|listing| listing.clinics)))
+    }
+
+    /// Yields clinics one by one as their pages complete. A page that fails
+    /// shows up as a single error item.
+    pub fn stream_clinics(&self) -> impl Stream<Item = Result<Clinic, ScrapeError>> + '_ {
+        self.stream_pages().flat_map(|(_, result)| match result {
+            Ok(clinics) => stream::iter(clinics.into_iter().map(Ok).collect::<Vec<_>>()),
+            Err(err) => stream::iter(vec![Err(err)]),
+        })
+    }
+
+    pub async fn scrape_page(&self, page_num: i32) -> Result<Vec<Clinic>, ScrapeError> {
+        self.scrape_listing(page_num)
+            .await
+            .map(|listing| listing.clinics)
+    }
+
+    pub async fn scrape_listing(&self, page_num: i32) -> Result<ListingPage, ScrapeError> {
+        println!("Scraping page {}.", page_num);
+
+        let page_url = self.source.page_url(page_num)?;
+        let body = self.fetch(page_num, &page_url).await?;
+        let mut listing = self.source.parse_page(page_num, &body, &page_url);
+
+        if listing.clinics.is_empty() && !self.source.is_genuinely_empty(&listing, &body) {
+            return Err(ScrapeError::MarkupChanged {
+                page: page_num,
+                url: page_url.to_string(),
+                saved: self.save_unparsed(page_num, &page_url, &body),
+            });
+        }
+
+        for clinic in &mut listing.clinics {
+            if let Some((name, tags)) = &self.label {
+                clinic.source_query = Some(name.clone());
+                clinic.tags = tags.clone();
+            }
+
+            if self.fetch_details {
+                clinic.details = self.lookup_details(page_num, clinic).await;
+                if clinic.website.is_none() {
+                    clinic.website = clinic
+                        .details
+                        .as_ref()
+                        .and_then(|details| details.website.clone());
+                }
+            } else if self.lookup_websites && clinic.website.is_none() {
+                clinic.website = self.lookup_website(page_num, clinic).await;
+            }
+        }
+
+        if listing.clinics.is_empty() {
+            println!("No results found for page {}.", page_num);
+        }
+
+        Ok(listing)
+    }
+
+    /// Writes the HTML of a page that could not be parsed to the unparsed
+    /// directory. Failures are reported and otherwise ignored.
+    fn save_unparsed(&self, page_num: i32, url: &Url, body: &str) -> Option<PathBuf> {
+        let dir = self.unparsed_dir.as_ref()?;
+        let path = dir.join(format!(
+            "page-{}-{:016x}.html",
+            page_num,
+            fingerprint(&[url.as_str()])
+        ));
+
+        match fs::create_dir_all(dir).and_then(|_| fs::write(&path, body)) {
+            Ok(()) => Some(path),
+            Err(err) => {
+                println!(
+                    "Failed to save {} to {}. Error: {}",
+                    url,
+                    path.display(),
+                    err
+                );
+                None
+            }
         }
+    }
+
+    async fn collect_report(&self, concurrency: usize) -> Result<ScrapeReport, ScrapeError> {
+        let mut report = ScrapeReport::default();
+        let mut results = Vec::new();
+        let mut pages = Box::pin(self.listing_stream(concurrency));
 
-        let body = res.text().await?;
+        while let Some((page_num, result)) = pages.next().await {
+            self.checkpoint_page(page_num, &result)?;
+            results.push((page_num, result));
+        }
 
-        let results = Document::from(&body[..])
-            .find(Class("js-entry-card-container"))
-            .map(|result| {
-                let name = result
-                    .find(Name("h2").and(Class("card-info-title")))
-                    .next()
-                    .unwrap();
+        results.sort_by_key(|(page_num, _)| *page_num);
 
-                let address = result.find(Class("card-info-address")).next().unwrap();
+        for (page_num, result) in results {
+            report.record(page_num, result.map(|listing| listing.clinics));
+        }
 
-                let address_text = address.text().trim().to_owned();
-                let postcode = address_text
-                    .split_whitespace()
-                    .find(|w| w.chars().all(|c| c.is_numeric()));
+        report.check_failure_ratio(self.max_failure_ratio)
+    }
 
-                let city = address_text.trim().split_whitespace().last();
+    /// Scrapes pages from 1 onwards, at most `concurrency` at a time, until
+    /// the last page is found or `max_pages` is reached. Pages that turn out
+    /// to lie past the end of the listing are left out, as are pages the
+    /// checkpoint already has.
+    fn listing_stream(
+        &self,
+        concurrency: usize,
+    ) -> impl Stream<Item = (i32, Result<ListingPage, ScrapeError>)> + '_ {
+        let progress = Arc::new(CrawlProgress::new(self.max_pages));
+        let (fetching, filtering) = (progress.clone(), progress.clone());
 
-                let phone = result
-                    .find(Name("a"))
-                    .filter_map(|n| n.attr("href"))
-                    .find(|href| href.starts_with("tel:"));
+        let completed = self.checkpoint.as_ref().map(|checkpoint| {
+            let file = checkpoint.lock().unwrap();
+            file.checkpoint.completed_pages.clone()
+        });
 
-                let website = result
-                    .find(Name("a"))
-                    .filter_map(|n| n.attr("href"))
-                    .find(|href| href.starts_with("http"));
+        stream::iter(1..=self.max_pages.unwrap_or(i32::MAX))
+            .filter(move |page_num| {
+                let done = completed
+                    .as_ref()
+                    .is_some_and(|completed| completed.contains(page_num));
+                future::ready(!done)
+            })
+            .take_while(move |page_num| future::ready(fetching.should_fetch(*page_num)))
+            .map(move |page_num| {
+                let progress = progress.clone();
+                async move {
+                    let guard = self.semaphore.acquire().await;
+                    let result = self.scrape_listing(page_num).await;
+                    drop(guard);
 
-                Clinic {
-                    name: name.text().trim().to_owned(),
-                    address: address_text.to_owned(),
-                    postcode: postcode.map(|p| p.to_owned()),
-                    city: city.map(|c| c.to_owned()),
-                    phone: phone.map(|p| p.to_owned()),
-                    website: website.map(|w| w.to_owned()),
+                    progress.update(self.source.as_ref(), page_num, &result);
+                    (page_num, result)
                 }
             })
-            .collect::<Vec<_>>();
+            .buffer_unordered(concurrency)
+            .filter(move |(page_num, _)| future::ready(filtering.is_within(*page_num)))
+    }
 
-        if results.len() == 0 {
-            println!("No results found for page {}.", page_num);
+    /// Scrapes the detail page at `detail_url`, found on listing page
+    /// `page_num`.
+    pub async fn scrape_details(
+        &self,
+        page_num: i32,
+        detail_url: &Url,
+    ) -> Result<ClinicDetails, ScrapeError> {
+        let body = self.fetch(page_num, detail_url).await?;
+        Ok(ClinicDetails::parse(&body, detail_url))
+    }
+
+    async fn lookup_details(&self, page_num: i32, clinic: &Clinic) -> Option<ClinicDetails> {
+        let detail_url = Url::parse(clinic.detail_url.as_deref()?).ok()?;
+
+        match self.scrape_details(page_num, &detail_url).await {
+            Ok(details) => Some(details),
+            Err(err) => {
+                println!(
+                    "Failed to scrape details of {}. Error: {}",
+                    clinic.name, err
+                );
+                None
+            }
         }
+    }
 
-        Ok(results)
+    /// Looks for the clinic's homepage on its detail page. Failures are
+    /// reported and leave the website empty.
+    async fn lookup_website(&self, page_num: i32, clinic: &Clinic) -> Option<String> {
+        let detail_url = Url::parse(clinic.detail_url.as_deref()?).ok()?;
+
+        match self.fetch(page_num, &detail_url).await {
+            Ok(body) => {
+                let document = Document::from(&body[..]);
+                let hrefs = document.find(Name("a")).filter_map(|n| n.attr("href"));
+                external_homepage(&detail_url, hrefs).map(String::from)
+            }
+            Err(err) => {
+                println!(
+                    "Failed to look up website of {}. Error: {}",
+                    clinic.name, err
+                );
+                None
+            }
+        }
     }
-}
 
-pub fn write_to_csv(clinics: Vec<Clinic>) -> Result<(), Box<dyn Error>> {
-    let file = File::create("clinics.csv")?;
-    let mut writer = Writer::from_writer(file);
+    /// Fetches `url` on behalf of listing page `page_num`, retrying as the
+    /// retry policy allows.
+    async fn fetch(&self, page_num: i32, url: &Url) -> Result<String, ScrapeError> {
+        if self.offline {
+            return self.read_cache(page_num, url);
+        }
 
-    writer.write_record(&["Name", "Address", "Postcode", "City", "Phone", "Website"])?;
+        let mut attempt = 1;
 
-    for clinic in clinics {
-        writer.write_record(&[
-            &clinic.name,
-            &clinic.address,
-            &clinic.postcode.unwrap_or_default(),
-            &clinic.city.unwrap_or_default(),
-            &clinic.phone.unwrap_or_default(),
-            &clinic.website.unwrap_or_default(),
-        ])?;
+        loop {
+            match self.try_fetch(page_num, url).await {
+                Ok(body) => return Ok(body),
+                Err(err)
+                    if attempt < self.retry_policy.max_attempts
+                        && self.retry_policy.is_retryable(&err) =>
+                {
+                    let delay = self.retry_policy.delay(attempt, &err);
+                    println!(
+                        "Retrying {} in {:?} (attempt {} of {}). Error: {}",
+                        url,
+                        delay,
+                        attempt + 1,
+                        self.retry_policy.max_attempts,
+                        err
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
     }
 
-    writer.flush()?;
+    async fn check_robots_txt(&self, page_num: i32, url: &Url) -> Result<(), ScrapeError> {
+        if self.ignore_robots_txt {
+            return Ok(());
+        }
+
+        let rules = self
+            .robots
+            .rules_for(&self.client, url, &self.user_agent, page_num)
+            .await?;
+
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_owned(),
+        };
+
+        if !rules.is_allowed(&path) {
+            return Err(ScrapeError::Disallowed {
+                page: page_num,
+                url: url.to_string(),
+            });
+        }
+
+        if let Some(delay) = rules.crawl_delay() {
+            self.robots.wait_crawl_delay(url, delay).await;
+        }
 
-    Ok(())
+        Ok(())
+    }
+
+    async fn try_fetch(&self, page_num: i32, url: &Url) -> Result<String, ScrapeError> {
+        let network = |source| ScrapeError::Network {
+            page: page_num,
+            source,
+        };
+
+        self.check_robots_txt(page_num, url).await?;
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter
+                .acquire(url.host_str().unwrap_or_default())
+                .await;
+        }
+
+        let cached = match &self.cache {
+            Some(cache) => cache.get(url)?,
+            None => None,
+        };
+
+        let mut request = self.client.get(url.clone());
+        if let Some((meta, _)) = &cached {
+            if let Some(etag) = &meta.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &meta.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let res = request.send().await.map_err(network)?;
+
+        if let (StatusCode::NOT_MODIFIED, Some((meta, body)), Some(cache)) =
+            (res.status(), cached, &self.cache)
+        {
+            if let Err(err) = cache.touch(url, meta) {
+                println!(
+                    "Failed to update the cache entry of {}. Error: {}",
+                    url, err
+                );
+            }
+            return Ok(body);
+        }
+
+        if !res.status().is_success() {
+            println!(
+                "Failed to fetch {} (page {}). Response status: {:?}",
+                url,
+                page_num,
+                res.status()
+            );
+            return Err(ScrapeError::Status {
+                page: page_num,
+                status: res.status(),
+                retry_after: parse_retry_after(res.headers()),
+            });
+        }
+
+        let headers = res.headers().clone();
+        let body = res.text().await.map_err(network)?;
+
+        if let Some(cache) = &self.cache {
+            if let Err(err) = cache.put(url, &headers, &body) {
+                println!("Failed to cache {}. Error: {}", url, err);
+            }
+        }
+
+        Ok(body)
+    }
+
+    fn read_cache(&self, page_num: i32, url: &Url) -> Result<String, ScrapeError> {
+        let cached = match &self.cache {
+            Some(cache) => cache.get(url)?,
+            None => None,
+        };
+
+        cached
+            .map(|(_, body)| body)
+            .ok_or_else(|| ScrapeError::NotCached {
+                page: page_num,
+                url: url.to_string(),
+            })
+    }
 }
@@ -0,0 +1,65 @@
+use reqwest::Url;
+
+/// Hosts whose links are never a business's own homepage.
+const NON_HOMEPAGE_HOSTS: [&str; 12] = [
+    "facebook.com",
+    "instagram.com",
+    "twitter.com",
+    "x.com",
+    "linkedin.com",
+    "youtube.com",
+    "google.com",
+    "google.ch",
+    "apple.com",
+    "search.ch",
+    "tel.search.ch",
+    "maps.apple.com",
+];
+
+/// Resolves an `href` against the page it was found on, ignoring links that
+/// are not http(s), like `tel:` and `mailto:`.
+pub(crate) fn resolve(base: &Url, href: &str) -> Option<Url> {
+    let url = base.join(href.trim()).ok()?;
+    matches!(url.scheme(), "http" | "https").then_some(url)
+}
+
+/// Whether `url` points at the scraped site itself, e.g. `local.ch` for a
+/// base URL on `www.local.ch`.
+pub(crate) fn is_same_site(url: &Url, base: &Url) -> bool {
+    match (url.host_str(), base.host_str()) {
+        (Some(host), Some(base_host)) => {
+            let site = base_host.strip_prefix("www.").unwrap_or(base_host);
+            host == site || host.ends_with(&format!(".{}", site))
+        }
+        _ => false,
+    }
+}
+
+/// The first link on the scraped site that leads to an entry's detail page.
+pub(crate) fn detail_url<'a>(base: &Url, hrefs: impl IntoIterator<Item = &'a str>) -> Option<Url> {
+    hrefs
+        .into_iter()
+        .filter_map(|href| resolve(base, href))
+        .find(|url| is_same_site(url, base) && url.path().contains("/d/"))
+}
+
+/// The first link that leaves the scraped site and is not a social network,
+/// map or app store link.
+pub(crate) fn external_homepage<'a>(
+    base: &Url,
+    hrefs: impl IntoIterator<Item = &'a str>,
+) -> Option<Url> {
+    hrefs
+        .into_iter()
+        .filter_map(|href| resolve(base, href))
+        .find(|url| !is_same_site(url, base) && !is_non_homepage(url))
+}
+
+pub(crate) fn is_non_homepage(url: &Url) -> bool {
+    let host = url.host_str().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    NON_HOMEPAGE_HOSTS
+        .iter()
+        .any(|blocked| host == *blocked || host.ends_with(&format!(".{}", blocked)))
+}
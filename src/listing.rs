@@ -0,0 +1,35 @@
+use crate::Clinic;
+
+/// One parsed search result page together with the pagination hints found
+/// on it.
+#[derive(Debug)]
+pub struct ListingPage {
+    pub page: i32,
+    pub clinics: Vec<Clinic>,
+    /// Total number of results announced in the page header.
+    pub total_results: Option<usize>,
+    /// Whether the page links to a following page, if it has pagination.
+    pub has_next: Option<bool>,
+    /// Result cards that were found but could not be parsed.
+    pub skipped_cards: usize,
+}
+
+impl ListingPage {
+    /// The last page number this page reveals, if any. An empty page means
+    /// the results ended on the previous one.
+    pub fn last_page(&self) -> Option<i32> {
+        if self.clinics.is_empty() {
+            return Some(self.page - 1);
+        }
+
+        if self.has_next == Some(false) {
+            return Some(self.page);
+        }
+
+        self.total_results.map(|total| {
+            let per_page = self.clinics.len();
+            let pages = total.div_ceil(per_page);
+            (pages as i32).max(self.page)
+        })
+    }
+}
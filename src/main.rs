@@ -1,26 +1,347 @@
+mod cli;
+
 use std::error::Error;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Parser;
+use futures::stream;
+use futures::StreamExt;
+use swiss_info_clinic_scraper::{
+    DedupReport, Deduplicator, JobFile, LocalCh, OutputOptions, RateLimit, RetryPolicy,
+    ScrapeError, ScrapeReport, Scraper, Selectors, StreamingWriter,
+};
 
-use swiss_info_clinic_scraper::{write_to_csv, Scraper};
+use cli::{BatchArgs, Cli, ClientArgs, Command, PageArgs, ScrapeArgs};
 
-const MAX_PAGES: i32 = 100;
-const MAX_PARALLEL: usize = 10;
+/// Exit status when pages came back with content but nothing the listing
+/// selectors recognize, which usually means the site's markup changed.
+const EXIT_MARKUP_CHANGED: u8 = 3;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let scraper = Scraper::new(
-        "https://www.local.ch/en/q".to_owned(),
-        "/Switzerland/clinique".to_owned(),
-        MAX_PAGES,
-        MAX_PARALLEL,
+async fn main() -> Result<ExitCode, Box<dyn Error>> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Scrape(args) => scrape(args).await,
+        Command::Page(args) => page(args).await,
+        Command::Batch(args) => batch(args).await,
+    }
+}
+
+async fn scrape(args: ScrapeArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let mut scraper = Scraper::from_source(
+        args.search.source()?,
+        args.max_pages,
+        if args.sequential { 1 } else { args.parallel },
+    )
+    .with_retry_policy(RetryPolicy {
+        max_attempts: args.max_attempts,
+        base_delay: Duration::from_millis(args.retry_delay_ms),
+        ..RetryPolicy::default()
+    })
+    .with_config(&args.client.config())?
+    .with_unparsed_dir(&args.unparsed_dir);
+
+    if !args.atomic {
+        scraper = scraper.with_checkpoint(args.checkpoint_path(), args.resume)?;
+    }
+
+    if args.client.ignore_robots_txt {
+        scraper = scraper.ignore_robots_txt();
+    }
+
+    scraper = with_cache(scraper, &args.client);
+
+    if args.lookup_websites {
+        scraper = scraper.with_website_lookup();
+    }
+
+    if args.details {
+        scraper = scraper.with_details();
+    }
+
+    if let Some(rate) = args.rate {
+        let delay = Duration::from_millis(args.politeness_delay_ms.unwrap_or_default());
+        scraper =
+            scraper.with_rate_limit(RateLimit::new(rate, args.burst)?.with_politeness_delay(delay));
+    }
+
+    let options = OutputOptions {
+        append: args.append || args.resume,
+        atomic: args.atomic,
+    };
+    let mut dedup = Deduplicator::new();
+    if options.append && !args.no_dedup && args.output.exists() {
+        dedup.seed(args.format.read_from_path(&args.output)?);
+    }
+
+    let mut writer = StreamingWriter::create(&args.output, args.format, options)?;
+    let mut report = ScrapeReport::default();
+    let mut pages = Box::pin(scraper.stream_pages());
+
+    while let Some((page_num, result)) = pages.next().await {
+        match &result {
+            Ok(clinics) if args.no_dedup => writer.write_page(clinics)?,
+            Ok(clinics) => {
+                let unique = clinics
+                    .iter()
+                    .filter(|clinic| dedup.insert((*clinic).clone()))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                writer.write_page(&unique)?;
+            }
+            Err(err) => println!("Page {} failed. Error: {}", page_num, err),
+        }
+
+        scraper.checkpoint_page(page_num, &result)?;
+
+        report.record(page_num, result);
+    }
+
+    // Duplicates found on later pages may have filled in fields of clinics
+    // that were already written.
+    if !dedup.report().merged_fields.is_empty() {
+        writer.rewrite(dedup.clinics())?;
+    }
+    writer.finish()?;
+
+    println!(
+        "Scraped {} clinics from {} pages ({} failed).",
+        report.clinics.len(),
+        report.pages_attempted,
+        report.failed_pages.len()
     );
 
-    // let clinics = scraper.scrape_pages().await?;
-    let clinics = scraper.scrape_pages_parallel().await?;
+    if !args.no_dedup {
+        report_dedup("", dedup.report());
+    }
+
+    if report_markup_changes(&report) {
+        return Ok(ExitCode::from(EXIT_MARKUP_CHANGED));
+    }
+
+    report.check_failure_ratio(Some(args.max_failure_ratio))?;
+
+    println!("Done!");
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn page(args: PageArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let mut scraper = Scraper::from_source(args.search.source()?, Some(1), 1)
+        .with_config(&args.client.config())?
+        .with_unparsed_dir(&args.unparsed_dir);
+
+    if args.client.ignore_robots_txt {
+        scraper = scraper.ignore_robots_txt();
+    }
+
+    scraper = with_cache(scraper, &args.client);
+
+    let mut report = ScrapeReport::default();
+    report.record(args.page, scraper.scrape_page(args.page).await);
+    if report_markup_changes(&report) {
+        return Ok(ExitCode::from(EXIT_MARKUP_CHANGED));
+    }
+
+    if let Some(failure) = report.failed_pages.pop() {
+        return Err(failure.error.into());
+    }
+
+    for clinic in &report.clinics {
+        println!("{:?}", clinic);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn batch(args: BatchArgs) -> Result<ExitCode, Box<dyn Error>> {
+    let job = JobFile::load(&args.job)?;
+    let selectors = match &args.selectors {
+        Some(path) => Selectors::load(path)?,
+        None => Selectors::default(),
+    };
+    let concurrency = args.parallel.unwrap_or(job.concurrency);
 
-    if let Err(err) = write_to_csv(clinics) {
-        println!("Failed to write to csv. Error: {}", err);
+    // Every query's scraper borrows the client, rate limit, robots.txt and
+    // page caches and concurrency budget of this one.
+    let mut shared = Scraper::new(job.site.clone(), String::new(), None, concurrency)
+        .with_config(&args.client.config())?;
+    if let Some(rate) = args.rate {
+        shared = shared.with_rate_limit(RateLimit::new(rate, args.burst)?);
+    }
+    shared = with_cache(shared, &args.client);
+
+    let mut scrapers = Vec::new();
+    let mut names = Vec::new();
+    for query in &job.queries {
+        let search = query.search_query()?;
+        let name = query.name(&search);
+
+        let source = LocalCh::for_query(&job.site, &search).with_selectors(selectors.clone());
+        let mut scraper = Scraper::from_source(source, job.max_pages(query), concurrency)
+            .with_retry_policy(RetryPolicy {
+                max_attempts: args.max_attempts,
+                base_delay: Duration::from_millis(args.retry_delay_ms),
+                ..RetryPolicy::default()
+            })
+            .share_with(&shared)
+            .with_unparsed_dir(&args.unparsed_dir)
+            .with_label(name.clone(), query.tags.clone());
+
+        if args.client.ignore_robots_txt {
+            scraper = scraper.ignore_robots_txt();
+        }
+
+        if args.details {
+            scraper = scraper.with_details();
+        }
+
+        scrapers.push(scraper);
+        names.push(name);
+    }
+
+    let options = OutputOptions::default();
+    let mut writers = Vec::new();
+    match &job.merge {
+        Some(path) => writers.push(StreamingWriter::create(path, job.format, options)?),
+        None => {
+            for query in &job.queries {
+                let path = query.output.as_ref().expect("validated by JobFile::load");
+                writers.push(StreamingWriter::create(path, job.format, options)?);
+            }
+        }
+    }
+
+    let mut dedups = job
+        .queries
+        .iter()
+        .map(|_| Deduplicator::new())
+        .collect::<Vec<_>>();
+    let mut reports = job
+        .queries
+        .iter()
+        .map(|_| ScrapeReport::default())
+        .collect::<Vec<_>>();
+    // Which query's unique clinics went to each writer, by their index in
+    // that query's deduplicator, so merged fields can be written back.
+    let mut written = writers.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+
+    let mut pages = stream::select_all(scrapers.iter().enumerate().map(|(i, scraper)| {
+        Box::pin(
+            scraper
+                .stream_pages()
+                .map(move |(page_num, result)| (i, page_num, result)),
+        )
+    }));
+
+    while let Some((i, page_num, result)) = pages.next().await {
+        let w = if job.merge.is_some() { 0 } else { i };
+
+        match &result {
+            Ok(clinics) if args.no_dedup => writers[w].write_page(clinics)?,
+            Ok(clinics) => {
+                let mut unique = Vec::new();
+                for clinic in clinics {
+                    if dedups[i].insert(clinic.clone()) {
+                        written[w].push((i, dedups[i].clinics().len() - 1));
+                        unique.push(clinic.clone());
+                    }
+                }
+                writers[w].write_page(&unique)?;
+            }
+            Err(err) => println!("{}: page {} failed. Error: {}", names[i], page_num, err),
+        }
+
+        reports[i].record(page_num, result);
+    }
+
+    for (mut writer, written) in writers.into_iter().zip(written) {
+        let merged = written
+            .iter()
+            .any(|&(i, _)| !dedups[i].report().merged_fields.is_empty());
+        if merged {
+            let clinics = written
+                .iter()
+                .map(|&(i, n)| dedups[i].clinics()[n].clone())
+                .collect::<Vec<_>>();
+            writer.rewrite(&clinics)?;
+        }
+        writer.finish()?;
+    }
+
+    for ((name, report), dedup) in names.iter().zip(&reports).zip(&dedups) {
+        println!(
+            "{}: scraped {} clinics from {} pages ({} failed).",
+            name,
+            report.clinics.len(),
+            report.pages_attempted,
+            report.failed_pages.len()
+        );
+        if !args.no_dedup {
+            report_dedup(&format!("{}: ", name), dedup.report());
+        }
+    }
+
+    let mut markup_changed = false;
+    for report in &reports {
+        markup_changed |= report_markup_changes(report);
+    }
+    if markup_changed {
+        return Ok(ExitCode::from(EXIT_MARKUP_CHANGED));
+    }
+
+    let mut failure = None;
+    for (name, report) in names.iter().zip(reports) {
+        if let Err(err) = report.check_failure_ratio(Some(args.max_failure_ratio)) {
+            println!("{}: {}", name, err);
+            failure.get_or_insert(err);
+        }
+    }
+    if let Some(err) = failure {
+        return Err(err.into());
     }
 
     println!("Done!");
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Applies the cache options; `share_with` passes them on in batch runs.
+fn with_cache(scraper: Scraper, client: &ClientArgs) -> Scraper {
+    match client.cache() {
+        Some(cache) if client.offline => scraper.offline(cache),
+        Some(cache) => scraper.with_cache(cache),
+        None => scraper,
+    }
+}
+
+/// Prints how many duplicates were skipped and which fields they filled in,
+/// each line starting with `prefix`.
+fn report_dedup(prefix: &str, report: &DedupReport) {
+    println!("{}Skipped {} duplicate entries.", prefix, report.duplicates);
+    for merge in &report.merged_fields {
+        println!(
+            "{}Filled in {} of {} from a duplicate.",
+            prefix, merge.field, merge.clinic
+        );
+    }
+}
+
+/// Lists the pages whose markup the selectors no longer match and returns
+/// whether there were any.
+fn report_markup_changes(report: &ScrapeReport) -> bool {
+    let mut changed = false;
+
+    for failure in report.markup_changes() {
+        changed = true;
+        println!("{}", failure.error);
+        if let ScrapeError::MarkupChanged {
+            saved: Some(path), ..
+        } = &failure.error
+        {
+            println!("Saved the page to {}.", path.display());
+        }
+    }
+
+    changed
 }
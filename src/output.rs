@@ -0,0 +1,537 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use csv::Writer;
+use serde::Deserialize;
+
+use crate::{Clinic, ScrapeError};
+
+const CSV_HEADER: [&str; 20] = [
+    "Name",
+    "Address",
+    "Street",
+    "House Number",
+    "Postcode",
+    "City",
+    "Canton",
+    "Phone",
+    "Phone Display",
+    "Phone Type",
+    "Other Phones",
+    "Website",
+    "Detail URL",
+    "Email",
+    "Fax",
+    "Opening Hours",
+    "Categories",
+    "Languages",
+    "Source Query",
+    "Query Tags",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Csv,
+    /// A pretty-printed JSON array
+    Json,
+    /// One JSON object per line
+    #[value(name = "jsonl")]
+    #[serde(rename = "jsonl")]
+    JsonLines,
+}
+
+impl OutputFormat {
+    pub fn write_to_path(
+        self,
+        clinics: &[Clinic],
+        path: impl AsRef<Path>,
+        options: OutputOptions,
+    ) -> Result<(), ScrapeError> {
+        match self {
+            Self::Csv => write_to_csv_with(clinics, path, options),
+            Self::Json => write_to_json(clinics, path, options),
+            Self::JsonLines => write_to_jsonl(clinics, path, options),
+        }
+    }
+
+    /// Reads back clinics written in this format, e.g. by an earlier run.
+    /// A file cut short by a crash yields the records written in full: an
+    /// unterminated last line or a JSON array that was never closed is not
+    /// an error.
+    pub fn read_from_path(self, path: impl AsRef<Path>) -> Result<Vec<Clinic>, ScrapeError> {
+        let text = fs::read_to_string(path)?;
+
+        match self {
+            Self::Json => read_json_array(&text),
+            Self::Csv => {
+                let text = &text[..complete_lines_len(&text)];
+                let mut reader = csv::Reader::from_reader(text.as_bytes());
+                let headers = csv_field_names(reader.headers()?);
+
+                reader
+                    .records()
+                    .map(|record| Ok(record?.deserialize(Some(&headers))?))
+                    .collect()
+            }
+            Self::JsonLines => text[..complete_lines_len(&text)]
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| Ok(serde_json::from_str(line)?))
+                .collect(),
+        }
+    }
+}
+
+/// How a path-based writer treats the file it writes to.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OutputOptions {
+    /// Add records to the end of an existing file instead of replacing it.
+    pub append: bool,
+    /// Write to a temporary file next to the target and rename it into
+    /// place once everything has been written.
+    pub atomic: bool,
+}
+
+/// A file opened according to `OutputOptions`. With `atomic` set, nothing
+/// reaches the target path until `commit` succeeds; dropping the file
+/// without committing removes the temporary file.
+pub struct OutputFile {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    temp_path: Option<PathBuf>,
+    was_empty: bool,
+}
+
+impl OutputFile {
+    pub fn create(path: impl AsRef<Path>, options: OutputOptions) -> Result<Self, ScrapeError> {
+        let path = path.as_ref().to_owned();
+
+        if !options.atomic {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(options.append)
+                .truncate(!options.append)
+                .open(&path)?;
+            let was_empty = file.seek(SeekFrom::End(0))? == 0;
+
+            return Ok(Self {
+                writer: BufWriter::new(file),
+                path,
+                temp_path: None,
+                was_empty,
+            });
+        }
+
+        let temp_path = temp_path_for(&path);
+        if options.append && path.exists() {
+            fs::copy(&path, &temp_path)?;
+        } else {
+            File::create(&temp_path)?;
+        }
+
+        let mut file = OpenOptions::new().append(true).open(&temp_path)?;
+        let was_empty = file.seek(SeekFrom::End(0))? == 0;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+            path,
+            temp_path: Some(temp_path),
+            was_empty,
+        })
+    }
+
+    /// Whether the file held no data before this writer opened it, i.e.
+    /// whether a header still needs to be written.
+    pub fn was_empty(&self) -> bool {
+        self.was_empty
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Size of the file in bytes, counting what it held before.
+    fn size(&mut self) -> Result<u64, ScrapeError> {
+        self.writer.flush()?;
+        Ok(self.writer.get_ref().metadata()?.len())
+    }
+
+    /// Cuts the file back to its first `len` bytes and continues writing
+    /// from there.
+    fn truncate(&mut self, len: u64) -> Result<(), ScrapeError> {
+        self.writer.flush()?;
+        self.writer.get_ref().set_len(len)?;
+        self.writer.get_mut().seek(SeekFrom::Start(len))?;
+        Ok(())
+    }
+
+    /// Flushes the data to disk and, for atomic writes, moves the temporary
+    /// file over the target path.
+    pub fn commit(mut self) -> Result<(), ScrapeError> {
+        self.writer.flush()?;
+        self.writer.get_ref().sync_all()?;
+
+        if let Some(temp_path) = self.temp_path.take() {
+            fs::rename(&temp_path, &self.path)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        if let Some(temp_path) = self.temp_path.take() {
+            let _ = fs::remove_file(temp_path);
+        }
+    }
+}
+
+/// Maps CSV column titles such as `House Number` to the matching `Clinic`
+/// field names, so files written with fewer columns still read back.
+fn csv_field_names(headers: &csv::StringRecord) -> csv::StringRecord {
+    headers
+        .iter()
+        .map(|header| header.trim().to_lowercase().replace(' ', "_"))
+        .collect()
+}
+
+/// Parses a JSON array of clinics. An array that ends early, as one left
+/// open by an interrupted `StreamingWriter` does, yields every element that
+/// was written in full.
+fn read_json_array(text: &str) -> Result<Vec<Clinic>, ScrapeError> {
+    Ok(scan_json_array(text)?.0)
+}
+
+/// Reads the elements of a possibly unfinished JSON array and returns them
+/// together with the byte offset right after the last complete one, or
+/// after the `[` if there are none, where more elements can be appended.
+/// Blank text yields no elements and offset 0.
+fn scan_json_array(text: &str) -> Result<(Vec<Clinic>, usize), ScrapeError> {
+    let malformed = |reason| ScrapeError::Json(serde::de::Error::custom(reason));
+    let skip_whitespace = |pos: usize| text.len() - text[pos..].trim_start().len();
+
+    let mut pos = skip_whitespace(0);
+    if pos == text.len() {
+        return Ok((Vec::new(), 0));
+    }
+    if !text[pos..].starts_with('[') {
+        return Err(malformed("expected a JSON array"));
+    }
+    pos += 1;
+
+    let mut clinics = Vec::new();
+    let mut end = pos;
+    loop {
+        pos = skip_whitespace(pos);
+        match text[pos..].chars().next() {
+            None => return Ok((clinics, end)),
+            Some(']') if text[pos + 1..].trim().is_empty() => return Ok((clinics, end)),
+            Some(']') => return Err(malformed("trailing characters after the JSON array")),
+            Some(',') if !clinics.is_empty() => pos = skip_whitespace(pos + 1),
+            Some(_) if !clinics.is_empty() => return Err(malformed("expected ',' or ']'")),
+            Some(_) => {}
+        }
+
+        let mut elements = serde_json::Deserializer::from_str(&text[pos..]).into_iter::<Clinic>();
+        match elements.next() {
+            Some(Ok(clinic)) => clinics.push(clinic),
+            Some(Err(err)) if err.is_eof() => return Ok((clinics, end)),
+            Some(Err(err)) => return Err(err.into()),
+            None => return Ok((clinics, end)),
+        }
+        pos += elements.byte_offset();
+        end = pos;
+    }
+}
+
+/// Length of `text` up to the end of its last complete line. Records are
+/// always written with a trailing newline, so anything after the last one
+/// is a record cut short.
+fn complete_lines_len(text: &str) -> usize {
+    text.rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()))
+}
+
+/// Writes the clinics as CSV, header included, to any writer such as a
+/// file, stdout or an in-memory buffer.
+pub fn write_csv<W: Write>(clinics: &[Clinic], writer: W) -> Result<(), ScrapeError> {
+    write_csv_records(clinics, writer, true)
+}
+
+pub fn write_to_csv(clinics: Vec<Clinic>, path: impl AsRef<Path>) -> Result<(), ScrapeError> {
+    write_to_csv_with(&clinics, path, OutputOptions::default())
+}
+
+/// Writes the clinics to a CSV file, only adding the header when the file
+/// starts out empty.
+pub fn write_to_csv_with(
+    clinics: &[Clinic],
+    path: impl AsRef<Path>,
+    options: OutputOptions,
+) -> Result<(), ScrapeError> {
+    let mut file = OutputFile::create(path, options)?;
+    let header = file.was_empty();
+
+    write_csv_records(clinics, &mut file, header)?;
+    file.commit()
+}
+
+fn write_csv_records<W: Write>(
+    clinics: &[Clinic],
+    writer: W,
+    header: bool,
+) -> Result<(), ScrapeError> {
+    let mut writer = Writer::from_writer(writer);
+
+    if header {
+        writer.write_record(CSV_HEADER)?;
+    }
+
+    for clinic in clinics {
+        let primary = clinic.phones().first();
+        let display = primary.map(|phone| phone.display.as_str());
+        let kind = primary.map(|phone| phone.kind.as_str());
+        let other_phones = clinic
+            .phones()
+            .iter()
+            .skip(1)
+            .map(|phone| phone.e164.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let details = clinic.details();
+        let email = details.and_then(|details| details.email.as_deref());
+        let fax = details.and_then(|details| details.fax.as_ref());
+        let opening_hours = details
+            .map(|details| {
+                details
+                    .opening_hours
+                    .iter()
+                    .map(|row| format!("{} {}", row.days, row.hours))
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            })
+            .unwrap_or_default();
+        let categories = details
+            .map(|details| details.categories.join("; "))
+            .unwrap_or_default();
+        let languages = details
+            .map(|details| details.languages.join("; "))
+            .unwrap_or_default();
+        let tags = clinic.tags().join("; ");
+
+        writer.write_record([
+            clinic.name(),
+            clinic.address(),
+            clinic.street().unwrap_or_default(),
+            clinic.house_number().unwrap_or_default(),
+            clinic.postcode().unwrap_or_default(),
+            clinic.city().unwrap_or_default(),
+            clinic.canton().unwrap_or_default(),
+            clinic.phone().unwrap_or_default(),
+            display.unwrap_or_default(),
+            kind.unwrap_or_default(),
+            &other_phones,
+            clinic.website().unwrap_or_default(),
+            clinic.detail_url().unwrap_or_default(),
+            email.unwrap_or_default(),
+            fax.map(|fax| fax.e164.as_str()).unwrap_or_default(),
+            &opening_hours,
+            &categories,
+            &languages,
+            clinic.source_query().unwrap_or_default(),
+            &tags,
+        ])?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the clinics as a pretty-printed JSON array. Missing values are
+/// written as `null`.
+pub fn write_json<W: Write>(clinics: &[Clinic], mut writer: W) -> Result<(), ScrapeError> {
+    serde_json::to_writer_pretty(&mut writer, clinics)?;
+    writeln!(writer)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes one JSON object per clinic, each on its own line.
+pub fn write_jsonl<W: Write>(clinics: &[Clinic], mut writer: W) -> Result<(), ScrapeError> {
+    for clinic in clinics {
+        serde_json::to_writer(&mut writer, clinic)?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Writes the clinics to a JSON file. Appending merges them into the array
+/// already in the file so the result stays a single valid document.
+pub fn write_to_json(
+    clinics: &[Clinic],
+    path: impl AsRef<Path>,
+    options: OutputOptions,
+) -> Result<(), ScrapeError> {
+    let path = path.as_ref();
+    let mut merged = Vec::new();
+
+    if options.append && path.exists() {
+        merged = read_json_array(&fs::read_to_string(path)?)?;
+    }
+
+    merged.extend_from_slice(clinics);
+
+    let options = OutputOptions {
+        append: false,
+        ..options
+    };
+    let mut file = OutputFile::create(path, options)?;
+    write_json(&merged, &mut file)?;
+    file.commit()
+}
+
+pub fn write_to_jsonl(
+    clinics: &[Clinic],
+    path: impl AsRef<Path>,
+    options: OutputOptions,
+) -> Result<(), ScrapeError> {
+    let mut file = OutputFile::create(path, options)?;
+
+    write_jsonl(clinics, &mut file)?;
+    file.commit()
+}
+
+/// Writes clinics to a file page by page, flushing after every batch so a
+/// crash keeps everything written so far. CSV and JSON Lines files stay
+/// valid at every point; a JSON array is only closed by `finish`. Appending
+/// to a file an interrupted run left behind first drops the record it was
+/// in the middle of writing, and continues a JSON array after its last
+/// element.
+pub struct StreamingWriter {
+    file: OutputFile,
+    format: OutputFormat,
+    written: usize,
+    /// Where the records of this run begin, and how many were in the file
+    /// before them.
+    start: (u64, usize),
+}
+
+impl StreamingWriter {
+    pub fn create(
+        path: impl AsRef<Path>,
+        format: OutputFormat,
+        options: OutputOptions,
+    ) -> Result<Self, ScrapeError> {
+        let path = path.as_ref();
+
+        // What to keep of a file an earlier run left behind: its records
+        // in full, without the closing `]` of a JSON array or a record a
+        // crash cut short. The rest is cut off in place, so the records
+        // themselves are never rewritten.
+        let mut existing = 0;
+        let mut keep = None;
+        if options.append && path.exists() {
+            let text = fs::read_to_string(path)?;
+            keep = Some(match format {
+                OutputFormat::Json => {
+                    let (clinics, end) = scan_json_array(&text)?;
+                    existing = clinics.len();
+                    end
+                }
+                OutputFormat::Csv | OutputFormat::JsonLines => complete_lines_len(&text),
+            });
+        }
+
+        let mut writer = Self {
+            file: OutputFile::create(path, options)?,
+            format,
+            written: existing,
+            start: (0, 0),
+        };
+        if let Some(len) = keep {
+            writer.file.truncate(len as u64)?;
+        }
+        let empty = writer.file.was_empty() || keep == Some(0);
+
+        match format {
+            OutputFormat::Csv if empty => write_csv_records(&[], &mut writer.file, true)?,
+            OutputFormat::Json if empty => write!(writer.file, "[")?,
+            _ => {}
+        }
+        writer.start = (writer.file.size()?, writer.written);
+
+        Ok(writer)
+    }
+
+    pub fn write_page(&mut self, clinics: &[Clinic]) -> Result<(), ScrapeError> {
+        match self.format {
+            OutputFormat::Csv => write_csv_records(clinics, &mut self.file, false)?,
+            OutputFormat::JsonLines => write_jsonl(clinics, &mut self.file)?,
+            OutputFormat::Json => {
+                for (i, clinic) in clinics.iter().enumerate() {
+                    let separator = if self.written + i == 0 { "\n" } else { ",\n" };
+                    write!(self.file, "{}  ", separator)?;
+                    serde_json::to_writer(&mut self.file, clinic)?;
+                }
+            }
+        }
+
+        self.written += clinics.len();
+        self.file.flush()?;
+        Ok(())
+    }
+
+    /// Replaces every clinic written since the writer was created with
+    /// `clinics`, e.g. once later duplicates filled in fields the first
+    /// copies were missing. Records that were in the file before stay.
+    pub fn rewrite(&mut self, clinics: &[Clinic]) -> Result<(), ScrapeError> {
+        let (len, written) = self.start;
+        self.file.truncate(len)?;
+        self.written = written;
+
+        self.write_page(clinics)
+    }
+
+    /// Number of clinics written by this writer so far.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    pub fn finish(mut self) -> Result<(), ScrapeError> {
+        if self.format == OutputFormat::Json {
+            let end = if self.written == 0 { "]\n" } else { "\n]\n" };
+            write!(self.file, "{}", end)?;
+        }
+
+        self.file.commit()
+    }
+}
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+
+/// Swiss geographic area codes, without the leading zero.
+const AREA_CODES: [&str; 22] = [
+    "21", "22", "24", "26", "27", "31", "32", "33", "34", "41", "43", "44", "51", "52", "55", "56",
+    "58", "61", "62", "71", "81", "91",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhoneKind {
+    Landline,
+    Mobile,
+    TollFree,
+    /// 084x numbers, where caller and callee split the cost.
+    SharedCost,
+    Premium,
+    /// A Swiss number outside the ranges above, such as a voicemail access.
+    Other,
+    Foreign,
+}
+
+impl PhoneKind {
+    /// The name used for this kind in serialized output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Landline => "landline",
+            Self::Mobile => "mobile",
+            Self::TollFree => "toll_free",
+            Self::SharedCost => "shared_cost",
+            Self::Premium => "premium",
+            Self::Other => "other",
+            Self::Foreign => "foreign",
+        }
+    }
+}
+
+/// A phone number in E.164 form, e.g. `+41614008080`, together with how it
+/// is usually written and what kind of line it is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PhoneNumber {
+    pub e164: String,
+    /// Grouped for reading, e.g. `+41 61 400 80 80`.
+    pub display: String,
+    pub kind: PhoneKind,
+}
+
+impl PhoneNumber {
+    /// Parses a number as found in `tel:` links or written by hand, in
+    /// international (`+41`, `0041`) or Swiss national (`061`) format.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let text = text.strip_prefix("tel:").unwrap_or(text).replace("(0)", "");
+
+        let international = text.starts_with('+') || text.starts_with("00");
+        let digits = text
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>();
+
+        let digits = if text.starts_with('+') {
+            digits
+        } else if let Some(digits) = digits.strip_prefix("00") {
+            digits.to_owned()
+        } else if let Some(national) = digits.strip_prefix('0') {
+            format!("41{}", national)
+        } else {
+            return None;
+        };
+
+        if !international && digits.len() != 11 {
+            return None;
+        }
+
+        match digits.strip_prefix("41") {
+            Some(national) if national.len() == 9 => {
+                let kind = classify(national);
+                Some(Self {
+                    e164: format!("+{}", digits),
+                    display: format!("+41 {}", group_swiss(national, kind)),
+                    kind,
+                })
+            }
+            Some(_) => None,
+            None if (8..=15).contains(&digits.len()) => Some(Self {
+                e164: format!("+{}", digits),
+                display: format!("+{}", digits),
+                kind: PhoneKind::Foreign,
+            }),
+            None => None,
+        }
+    }
+}
+
+/// Classifies a 9-digit Swiss national number by its prefix.
+fn classify(national: &str) -> PhoneKind {
+    let prefix2 = &national[..2];
+    let prefix3 = &national[..3];
+
+    match prefix3 {
+        "800" => return PhoneKind::TollFree,
+        "840" | "842" | "844" | "848" => return PhoneKind::SharedCost,
+        "900" | "901" | "906" => return PhoneKind::Premium,
+        _ => {}
+    }
+
+    match prefix2 {
+        "74" | "75" | "76" | "77" | "78" | "79" => PhoneKind::Mobile,
+        code if AREA_CODES.contains(&code) => PhoneKind::Landline,
+        _ => PhoneKind::Other,
+    }
+}
+
+/// Groups a national number the Swiss way: `61 400 80 80` for area and
+/// mobile codes, `800 123 456` for service numbers.
+fn group_swiss(national: &str, kind: PhoneKind) -> String {
+    if matches!(
+        kind,
+        PhoneKind::TollFree | PhoneKind::SharedCost | PhoneKind::Premium
+    ) {
+        return format!("{} {} {}", &national[..3], &national[3..6], &national[6..]);
+    }
+
+    format!(
+        "{} {} {} {}",
+        &national[..2],
+        &national[2..5],
+        &national[5..7],
+        &national[7..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_swiss_and_foreign_numbers() {
+        let cases = [
+            (
+                "tel:+41614008080",
+                "+41614008080",
+                "+41 61 400 80 80",
+                PhoneKind::Landline,
+            ),
+            (
+                "0041 61 400 80 80",
+                "+41614008080",
+                "+41 61 400 80 80",
+                PhoneKind::Landline,
+            ),
+            (
+                "061 400 80 80",
+                "+41614008080",
+                "+41 61 400 80 80",
+                PhoneKind::Landline,
+            ),
+            (
+                "+41 (0)44 387 30 00",
+                "+41443873000",
+                "+41 44 387 30 00",
+                PhoneKind::Landline,
+            ),
+            (
+                "079 123 45 67",
+                "+41791234567",
+                "+41 79 123 45 67",
+                PhoneKind::Mobile,
+            ),
+            (
+                "0800 123 456",
+                "+41800123456",
+                "+41 800 123 456",
+                PhoneKind::TollFree,
+            ),
+            (
+                "0848 123 456",
+                "+41848123456",
+                "+41 848 123 456",
+                PhoneKind::SharedCost,
+            ),
+            (
+                "0900 123 456",
+                "+41900123456",
+                "+41 900 123 456",
+                PhoneKind::Premium,
+            ),
+            (
+                "086 079 12 34",
+                "+41860791234",
+                "+41 86 079 12 34",
+                PhoneKind::Other,
+            ),
+            (
+                "+49 30 1234567",
+                "+49301234567",
+                "+49301234567",
+                PhoneKind::Foreign,
+            ),
+            (
+                "0033 1 23 45 67 89",
+                "+33123456789",
+                "+33123456789",
+                PhoneKind::Foreign,
+            ),
+        ];
+
+        for (text, e164, display, kind) in cases {
+            let phone = PhoneNumber::parse(text).unwrap_or_else(|| panic!("{:?}", text));
+            assert_eq!(phone.e164, e164, "{:?}", text);
+            assert_eq!(phone.display, display, "{:?}", text);
+            assert_eq!(phone.kind, kind, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_numbers() {
+        for text in [
+            "",
+            "tel:",
+            "61 400 80 80",
+            "061 400 80",
+            "+41 61 400 80 80 1",
+            "+49 30",
+        ] {
+            assert_eq!(PhoneNumber::parse(text), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn classifies_by_prefix() {
+        let cases = [
+            ("614008080", PhoneKind::Landline),
+            ("913001234", PhoneKind::Landline),
+            ("741234567", PhoneKind::Mobile),
+            ("791234567", PhoneKind::Mobile),
+            ("800123456", PhoneKind::TollFree),
+            ("840123456", PhoneKind::SharedCost),
+            ("842123456", PhoneKind::SharedCost),
+            ("844123456", PhoneKind::SharedCost),
+            ("901123456", PhoneKind::Premium),
+            ("906123456", PhoneKind::Premium),
+            ("860123456", PhoneKind::Other),
+            ("201234567", PhoneKind::Other),
+        ];
+
+        for (national, kind) in cases {
+            assert_eq!(classify(national), kind, "{:?}", national);
+        }
+    }
+
+    #[test]
+    fn groups_by_kind() {
+        assert_eq!(
+            group_swiss("614008080", PhoneKind::Landline),
+            "61 400 80 80"
+        );
+        assert_eq!(group_swiss("791234567", PhoneKind::Mobile), "79 123 45 67");
+        assert_eq!(group_swiss("800123456", PhoneKind::TollFree), "800 123 456");
+        assert_eq!(
+            group_swiss("848123456", PhoneKind::SharedCost),
+            "848 123 456"
+        );
+        assert_eq!(group_swiss("906123456", PhoneKind::Premium), "906 123 456");
+    }
+}
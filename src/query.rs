@@ -0,0 +1,230 @@
+use clap::ValueEnum;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::Deserialize;
+
+use crate::ScrapeError;
+
+pub const LOCAL_CH: &str = "https://www.local.ch";
+
+/// Characters escaped in a URL path segment.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+const CANTONS: [(&str, &str); 26] = [
+    ("AG", "Aargau"),
+    ("AI", "Appenzell Innerrhoden"),
+    ("AR", "Appenzell Ausserrhoden"),
+    ("BE", "Bern"),
+    ("BL", "Basel-Landschaft"),
+    ("BS", "Basel-Stadt"),
+    ("FR", "Fribourg"),
+    ("GE", "Genève"),
+    ("GL", "Glarus"),
+    ("GR", "Graubünden"),
+    ("JU", "Jura"),
+    ("LU", "Luzern"),
+    ("NE", "Neuchâtel"),
+    ("NW", "Nidwalden"),
+    ("OW", "Obwalden"),
+    ("SG", "St. Gallen"),
+    ("SH", "Schaffhausen"),
+    ("SO", "Solothurn"),
+    ("SZ", "Schwyz"),
+    ("TG", "Thurgau"),
+    ("TI", "Ticino"),
+    ("UR", "Uri"),
+    ("VD", "Vaud"),
+    ("VS", "Valais"),
+    ("ZG", "Zug"),
+    ("ZH", "Zürich"),
+];
+
+/// The official name of the canton with the given two-letter code.
+pub(crate) fn canton_name(code: &str) -> Option<&'static str> {
+    CANTONS
+        .iter()
+        .find(|(canton, _)| canton.eq_ignore_ascii_case(code))
+        .map(|(_, name)| *name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    De,
+    Fr,
+    It,
+}
+
+impl Language {
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+            Self::Fr => "fr",
+            Self::It => "it",
+        }
+    }
+}
+
+/// Business categories with the search term the site uses for them in each
+/// language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Clinic,
+    Hospital,
+    Dentist,
+    Pharmacy,
+    Physiotherapist,
+    Doctor,
+    Veterinarian,
+}
+
+impl Category {
+    pub fn term(self, language: Language) -> &'static str {
+        use Language::*;
+
+        match (self, language) {
+            (Self::Clinic, En) => "clinic",
+            (Self::Clinic, De) => "Klinik",
+            (Self::Clinic, Fr) => "clinique",
+            (Self::Clinic, It) => "clinica",
+            (Self::Hospital, En) => "hospital",
+            (Self::Hospital, De) => "Spital",
+            (Self::Hospital, Fr) => "hôpital",
+            (Self::Hospital, It) => "ospedale",
+            (Self::Dentist, En) => "dentist",
+            (Self::Dentist, De) => "Zahnarzt",
+            (Self::Dentist, Fr) => "dentiste",
+            (Self::Dentist, It) => "dentista",
+            (Self::Pharmacy, En) => "pharmacy",
+            (Self::Pharmacy, De) => "Apotheke",
+            (Self::Pharmacy, Fr) => "pharmacie",
+            (Self::Pharmacy, It) => "farmacia",
+            (Self::Physiotherapist, En) => "physiotherapist",
+            (Self::Physiotherapist, De) => "Physiotherapie",
+            (Self::Physiotherapist, Fr) => "physiothérapeute",
+            (Self::Physiotherapist, It) => "fisioterapista",
+            (Self::Doctor, En) => "doctor",
+            (Self::Doctor, De) => "Arzt",
+            (Self::Doctor, Fr) => "médecin",
+            (Self::Doctor, It) => "medico",
+            (Self::Veterinarian, En) => "veterinarian",
+            (Self::Veterinarian, De) => "Tierarzt",
+            (Self::Veterinarian, Fr) => "vétérinaire",
+            (Self::Veterinarian, It) => "veterinario",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum What {
+    Text(String),
+    Category(Category),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Where {
+    /// All of Switzerland.
+    #[default]
+    Country,
+    /// A canton by its two-letter code, e.g. `ZH`.
+    Canton(String),
+    City(String),
+    Postcode(String),
+}
+
+impl Where {
+    pub fn canton(code: &str) -> Result<Self, ScrapeError> {
+        canton_name(code)
+            .map(|_| Self::Canton(code.to_ascii_uppercase()))
+            .ok_or_else(|| ScrapeError::Config(format!("unknown canton {:?}", code)))
+    }
+
+    pub fn postcode(postcode: &str) -> Result<Self, ScrapeError> {
+        if postcode.len() == 4 && postcode.chars().all(|c| c.is_ascii_digit()) {
+            Ok(Self::Postcode(postcode.to_owned()))
+        } else {
+            Err(ScrapeError::Config(format!(
+                "{:?} is not a 4-digit postcode",
+                postcode
+            )))
+        }
+    }
+
+    fn segment(&self, language: Language) -> String {
+        match self {
+            Self::Country => match language {
+                Language::En => "Switzerland",
+                Language::De => "Schweiz",
+                Language::Fr => "Suisse",
+                Language::It => "Svizzera",
+            }
+            .to_owned(),
+            Self::Canton(code) => canton_name(code).unwrap_or(code).to_owned(),
+            Self::City(city) => city.clone(),
+            Self::Postcode(postcode) => postcode.clone(),
+        }
+    }
+}
+
+/// A search on local.ch: what to look for, where, and in which language the
+/// site should answer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SearchQuery {
+    pub what: What,
+    pub location: Where,
+    pub language: Language,
+}
+
+impl SearchQuery {
+    pub fn new(what: What, location: Where) -> Self {
+        Self {
+            what,
+            location,
+            language: Language::default(),
+        }
+    }
+
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// The search URL without the location and search term, e.g.
+    /// `https://www.local.ch/en/q` for `site` `https://www.local.ch`.
+    pub fn base_url(&self, site: &str) -> String {
+        format!("{}/{}/q", site.trim_end_matches('/'), self.language.code())
+    }
+
+    /// The location and search term part of the URL, e.g.
+    /// `/Switzerland/clinic`.
+    pub fn path(&self) -> String {
+        let what = match &self.what {
+            What::Text(text) => text.as_str(),
+            What::Category(category) => category.term(self.language),
+        };
+
+        format!(
+            "/{}/{}",
+            utf8_percent_encode(&self.location.segment(self.language), SEGMENT),
+            utf8_percent_encode(what.trim(), SEGMENT)
+        )
+    }
+
+    pub fn url(&self, site: &str) -> String {
+        format!("{}{}", self.base_url(site), self.path())
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::ScrapeError;
+
+/// Token bucket settings applied separately to every host the scraper talks
+/// to.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    pub requests_per_second: f64,
+    /// Requests that may be sent back-to-back before the rate applies.
+    pub burst: u32,
+    /// Upper bound of a random extra pause added before every request.
+    pub politeness_delay: Duration,
+}
+
+impl RateLimit {
+    /// Fails unless the rate is a positive, finite number and the burst
+    /// allows at least one request.
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self, ScrapeError> {
+        if !(requests_per_second > 0.0 && requests_per_second.is_finite()) {
+            return Err(ScrapeError::Config(format!(
+                "rate limit must be a positive number of requests per second, got {}",
+                requests_per_second
+            )));
+        }
+        if burst == 0 {
+            return Err(ScrapeError::Config(
+                "rate limit burst must be at least 1".to_owned(),
+            ));
+        }
+
+        Ok(Self {
+            requests_per_second,
+            burst,
+            politeness_delay: Duration::ZERO,
+        })
+    }
+
+    pub fn with_politeness_delay(mut self, max_delay: Duration) -> Self {
+        self.politeness_delay = max_delay;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Waits until a request to `host` may be sent.
+    pub async fn acquire(&self, host: &str) {
+        let wait = self.reserve(host) + self.politeness_delay();
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes a token from the host's bucket, letting it go negative so that
+    /// concurrent callers queue up behind each other, and returns how long
+    /// the caller has to wait for its token.
+    fn reserve(&self, host: &str) -> Duration {
+        let now = Instant::now();
+        let burst = f64::from(self.limit.burst);
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(host.to_owned()).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.limit.requests_per_second).min(burst);
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.limit.requests_per_second)
+        }
+    }
+
+    fn politeness_delay(&self) -> Duration {
+        if self.limit.politeness_delay.is_zero() {
+            return Duration::ZERO;
+        }
+
+        rand::thread_rng().gen_range(Duration::ZERO..=self.limit.politeness_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_rejects_rates_that_allow_no_requests() {
+        assert!(RateLimit::new(0.5, 2).is_ok());
+        assert!(RateLimit::new(0.0, 1).is_err());
+        assert!(RateLimit::new(-1.0, 1).is_err());
+        assert!(RateLimit::new(f64::NAN, 1).is_err());
+        assert!(RateLimit::new(f64::INFINITY, 1).is_err());
+        assert!(RateLimit::new(1.0, 0).is_err());
+    }
+}
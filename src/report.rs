@@ -0,0 +1,68 @@
+use reqwest::StatusCode;
+
+use crate::{Clinic, ScrapeError};
+
+/// Outcome of a multi-page scrape: every clinic that was parsed plus the
+/// pages that could not be fetched.
+#[derive(Debug, Default)]
+pub struct ScrapeReport {
+    pub clinics: Vec<Clinic>,
+    pub failed_pages: Vec<PageFailure>,
+    pub pages_attempted: usize,
+}
+
+#[derive(Debug)]
+pub struct PageFailure {
+    pub page: i32,
+    pub status: Option<StatusCode>,
+    pub error: ScrapeError,
+}
+
+impl ScrapeReport {
+    pub fn record(&mut self, page: i32, result: Result<Vec<Clinic>, ScrapeError>) {
+        self.pages_attempted += 1;
+
+        match result {
+            Ok(clinics) => self.clinics.extend(clinics),
+            Err(error) => self.failed_pages.push(PageFailure {
+                page,
+                status: error.status(),
+                error,
+            }),
+        }
+    }
+
+    pub fn failure_ratio(&self) -> f64 {
+        if self.pages_attempted == 0 {
+            return 0.0;
+        }
+
+        self.failed_pages.len() as f64 / self.pages_attempted as f64
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.failed_pages.is_empty()
+    }
+
+    /// Pages that came back with content the listing selectors found
+    /// nothing in.
+    pub fn markup_changes(&self) -> impl Iterator<Item = &PageFailure> {
+        self.failed_pages
+            .iter()
+            .filter(|failure| matches!(failure.error, ScrapeError::MarkupChanged { .. }))
+    }
+
+    /// Turns the report into an error when more than `max_ratio` of the
+    /// attempted pages failed.
+    pub fn check_failure_ratio(self, max_ratio: Option<f64>) -> Result<Self, ScrapeError> {
+        match max_ratio {
+            Some(max_ratio) if self.failure_ratio() > max_ratio => {
+                Err(ScrapeError::TooManyFailures {
+                    failed: self.failed_pages.len(),
+                    attempted: self.pages_attempted,
+                })
+            }
+            _ => Ok(self),
+        }
+    }
+}
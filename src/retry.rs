@@ -0,0 +1,116 @@
+use std::time::{Duration, SystemTime};
+
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+use crate::ScrapeError;
+
+/// Controls how often and how patiently a failed page fetch is repeated.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    /// Longest wait before a retry. A `Retry-After` asking for more ends
+    /// the retries instead of being cut short.
+    pub max_delay: Duration,
+    /// Randomize each delay between half and all of its computed value.
+    pub jitter: bool,
+    pub retryable_statuses: Vec<StatusCode>,
+    /// Retry connection failures and timeouts.
+    pub retry_network_errors: bool,
+    /// Wait for the server's `Retry-After` header when one is sent.
+    pub honor_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retryable_statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_network_errors: true,
+            honor_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt and never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_retryable(&self, error: &ScrapeError) -> bool {
+        match error {
+            ScrapeError::Status {
+                retry_after: Some(retry_after),
+                ..
+            } if self.honor_retry_after && *retry_after > self.max_delay => false,
+            ScrapeError::Status { status, .. } => self.retryable_statuses.contains(status),
+            ScrapeError::Network { source, .. } => {
+                self.retry_network_errors
+                    && (source.is_timeout() || source.is_connect() || source.is_request())
+            }
+            ScrapeError::RobotsUnavailable { source, .. } => self.is_retryable(source),
+            _ => false,
+        }
+    }
+
+    /// Delay before retrying `error`, which ended the given (1-based) attempt.
+    pub fn delay(&self, attempt: u32, error: &ScrapeError) -> Duration {
+        if let ScrapeError::RobotsUnavailable { source, .. } = error {
+            return self.delay(attempt, source);
+        }
+
+        if let ScrapeError::Status {
+            retry_after: Some(retry_after),
+            ..
+        } = error
+        {
+            if self.honor_retry_after {
+                return *retry_after;
+            }
+        }
+
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        if self.jitter && !delay.is_zero() {
+            let half = delay / 2;
+            half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Parses a `Retry-After` header given either as seconds or as an HTTP date.
+pub(crate) fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => Some(
+            httpdate::parse_http_date(value)
+                .ok()?
+                .duration_since(SystemTime::now())
+                .unwrap_or_default(),
+        ),
+    }
+}
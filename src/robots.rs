@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use reqwest::{Client, Url};
+
+use crate::retry::parse_retry_after;
+use crate::ScrapeError;
+
+/// Longest `Crawl-delay` honored; larger values are clamped to it.
+const MAX_CRAWL_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// The robots.txt rules that apply to one user agent on one host.
+#[derive(Debug, Clone, Default)]
+pub struct RobotsRules {
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Debug, Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsRules {
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![Rule {
+                allow: false,
+                pattern: "/".to_owned(),
+            }],
+            crawl_delay: None,
+        }
+    }
+
+    /// Parses a robots.txt file and keeps the groups addressed to
+    /// `user_agent`, falling back to the `*` groups when none are.
+    pub fn parse(text: &str, user_agent: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut reading_agents = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !reading_agents {
+                        groups.push(Group::default());
+                        reading_agents = true;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    reading_agents = false;
+                    if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: value.to_owned(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    reading_agents = false;
+                    if let (Some(group), Ok(seconds)) = (groups.last_mut(), value.parse::<f64>()) {
+                        if let Ok(delay) = Duration::try_from_secs_f64(seconds) {
+                            group.crawl_delay = Some(delay.min(MAX_CRAWL_DELAY));
+                        } else if seconds > 0.0 {
+                            group.crawl_delay = Some(MAX_CRAWL_DELAY);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let token = product_token(user_agent);
+        let specific = groups
+            .iter()
+            .filter(|group| {
+                group
+                    .agents
+                    .iter()
+                    .any(|agent| agent != "*" && token.starts_with(agent.as_str()))
+            })
+            .collect::<Vec<_>>();
+
+        let selected = if specific.is_empty() {
+            groups
+                .iter()
+                .filter(|group| group.agents.iter().any(|agent| agent == "*"))
+                .collect()
+        } else {
+            specific
+        };
+
+        Self {
+            rules: selected
+                .iter()
+                .flat_map(|group| group.rules.iter().cloned())
+                .collect(),
+            crawl_delay: selected.iter().filter_map(|group| group.crawl_delay).max(),
+        }
+    }
+
+    /// Checks a path (including its query string) against the rules. The
+    /// longest matching pattern wins and `Allow` wins ties.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
+
+fn product_token(user_agent: &str) -> String {
+    user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Matches a robots.txt path pattern, where `*` stands for any sequence of
+/// characters and a trailing `$` anchors the pattern at the end of the path.
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts = parts.collect::<Vec<_>>();
+    for (i, part) in parts.iter().enumerate() {
+        let is_last = i + 1 == parts.len();
+        if is_last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    !anchored || rest.is_empty()
+}
+
+/// Parsed robots.txt files per origin, plus the time each origin may next be
+/// contacted under its `Crawl-delay`.
+#[derive(Debug, Default)]
+pub(crate) struct RobotsCache {
+    rules: tokio::sync::Mutex<HashMap<String, Arc<RobotsRules>>>,
+    next_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl RobotsCache {
+    /// The rules for the origin of `url`, fetched on behalf of listing page
+    /// `page_num`. Only successful fetches are cached, so a robots.txt that
+    /// could not be fetched is tried again with the next request.
+    pub async fn rules_for(
+        &self,
+        client: &Client,
+        url: &Url,
+        user_agent: &str,
+        page_num: i32,
+    ) -> Result<Arc<RobotsRules>, ScrapeError> {
+        let origin = url.origin().ascii_serialization();
+        let mut rules = self.rules.lock().await;
+
+        if let Some(cached) = rules.get(&origin) {
+            return Ok(cached.clone());
+        }
+
+        let fetched = Arc::new(fetch_rules(client, &origin, user_agent, page_num).await?);
+        rules.insert(origin, fetched.clone());
+        Ok(fetched)
+    }
+
+    /// Waits until the crawl delay since the previous request to the same
+    /// origin has passed.
+    pub async fn wait_crawl_delay(&self, url: &Url, delay: Duration) {
+        let now = Instant::now();
+        let slot = {
+            let mut next_request = self.next_request.lock().unwrap();
+            let slot = next_request
+                .get(&url.origin().ascii_serialization())
+                .map_or(now, |next| (*next).max(now));
+            next_request.insert(url.origin().ascii_serialization(), slot + delay);
+            slot
+        };
+
+        tokio::time::sleep_until(slot.into()).await;
+    }
+}
+
+/// Follows RFC 9309: a missing robots.txt allows everything, while one
+/// that cannot be fetched leaves the rules unknown, which the caller must
+/// treat as disallowing everything until a later fetch succeeds.
+async fn fetch_rules(
+    client: &Client,
+    origin: &str,
+    user_agent: &str,
+    page_num: i32,
+) -> Result<RobotsRules, ScrapeError> {
+    let robots_url = format!("{}/robots.txt", origin);
+    let unavailable = |source| ScrapeError::RobotsUnavailable {
+        page: page_num,
+        url: robots_url.clone(),
+        source: Box::new(source),
+    };
+    let network = |source| {
+        unavailable(ScrapeError::Network {
+            page: page_num,
+            source,
+        })
+    };
+
+    let res = client.get(&robots_url).send().await.map_err(network)?;
+
+    let status = res.status();
+    if status.is_client_error() {
+        return Ok(RobotsRules::allow_all());
+    }
+    if !status.is_success() {
+        return Err(unavailable(ScrapeError::Status {
+            page: page_num,
+            status,
+            retry_after: parse_retry_after(res.headers()),
+        }));
+    }
+
+    let text = res.text().await.map_err(network)?;
+    Ok(RobotsRules::parse(&text, user_agent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_AGENT: &str = "clinic-scraper/0.1 (+https://example.com)";
+
+    #[test]
+    fn specific_group_replaces_the_wildcard_group() {
+        let text = "\
+User-agent: *
+Disallow: /
+
+User-agent: Googlebot
+User-agent: clinic-scraper
+Disallow: /private
+Crawl-delay: 2
+";
+        let rules = RobotsRules::parse(text, USER_AGENT);
+
+        assert!(rules.is_allowed("/en/q/clinique"));
+        assert!(!rules.is_allowed("/private/page"));
+        assert_eq!(rules.crawl_delay(), Some(Duration::from_secs(2)));
+
+        let other = RobotsRules::parse(text, "other-bot/1.0");
+        assert!(!other.is_allowed("/en/q/clinique"));
+        assert_eq!(other.crawl_delay(), None);
+    }
+
+    #[test]
+    fn longest_match_wins_and_allow_wins_ties() {
+        let rules = RobotsRules::parse(
+            "\
+User-agent: *
+Disallow: /en/
+Allow: /en/q/
+Disallow: /en/q/private
+Allow: /fr
+Disallow: /fr
+",
+            USER_AGENT,
+        );
+
+        assert!(!rules.is_allowed("/en/d/entry"));
+        assert!(rules.is_allowed("/en/q/clinique"));
+        assert!(!rules.is_allowed("/en/q/private/x"));
+        assert!(rules.is_allowed("/fr/q"));
+        assert!(rules.is_allowed("/de/q"));
+    }
+
+    #[test]
+    fn wildcards_and_end_anchors_match() {
+        assert!(matches("/*?page=", "/en/q/clinique?page=2"));
+        assert!(!matches("/*?page=", "/en/q/clinique"));
+        assert!(matches("/*.pdf$", "/files/report.pdf"));
+        assert!(!matches("/*.pdf$", "/files/report.pdf?download=1"));
+        assert!(matches("/en/q$", "/en/q"));
+        assert!(!matches("/en/q$", "/en/q/clinique"));
+        assert!(matches("/en/*/clinique*", "/en/q/clinique?page=1"));
+    }
+
+    #[test]
+    fn crawl_delay_is_clamped() {
+        let parse = |delay: &str| {
+            let text = format!("User-agent: *\nCrawl-delay: {}\n", delay);
+            RobotsRules::parse(&text, USER_AGENT).crawl_delay()
+        };
+
+        assert_eq!(parse("1.5"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse("1e20"), Some(MAX_CRAWL_DELAY));
+        assert_eq!(parse("inf"), Some(MAX_CRAWL_DELAY));
+        assert_eq!(parse("-1"), None);
+        assert_eq!(parse("NaN"), None);
+        assert_eq!(parse("soon"), None);
+    }
+}
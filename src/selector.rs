@@ -0,0 +1,470 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use select::node::Node;
+use select::predicate::Predicate;
+use serde::Deserialize;
+
+use crate::ScrapeError;
+
+/// A CSS selector, limited to what listing markup needs: type, `.class`,
+/// `#id` and `[attr]`, `[attr=value]`, `[attr^=value]`, `[attr$=value]` or
+/// `[attr*=value]` tests, joined by descendant (` `) or child (`>`)
+/// combinators. Comma-separated selectors match if any of them does.
+/// Pseudo-classes such as `:first-child` are rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Selector {
+    source: String,
+    alternatives: Vec<Vec<(Combinator, Compound)>>,
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Compound {
+    name: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attrs: Vec<AttrTest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AttrTest {
+    name: String,
+    op: AttrOp,
+    value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AttrOp {
+    Exists,
+    Equals,
+    Prefix,
+    Suffix,
+    Contains,
+}
+
+impl Selector {
+    pub fn parse(source: &str) -> Result<Self, ScrapeError> {
+        let invalid = |reason: &str| {
+            ScrapeError::Config(format!("invalid selector {:?}: {}", source, reason))
+        };
+
+        let alternatives = split_alternatives(source)
+            .into_iter()
+            .map(|alternative| parse_steps(alternative).map_err(|reason| invalid(&reason)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            source: source.trim().to_owned(),
+            alternatives,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl TryFrom<String> for Selector {
+    type Error = ScrapeError;
+
+    fn try_from(source: String) -> Result<Self, Self::Error> {
+        Self::parse(&source)
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Predicate for &Selector {
+    fn matches(&self, node: &Node) -> bool {
+        self.alternatives
+            .iter()
+            .any(|steps| matches_steps(steps, *node))
+    }
+}
+
+/// Matches the last step against `node` and the earlier ones against its
+/// ancestors, as the combinators between them require.
+fn matches_steps(steps: &[(Combinator, Compound)], node: Node) -> bool {
+    let Some(((combinator, last), rest)) = steps.split_last() else {
+        return true;
+    };
+
+    if !last.matches(&node) {
+        return false;
+    }
+    if rest.is_empty() {
+        return true;
+    }
+
+    let mut parent = node.parent();
+    while let Some(ancestor) = parent {
+        if matches_steps(rest, ancestor) {
+            return true;
+        }
+        if *combinator == Combinator::Child {
+            return false;
+        }
+        parent = ancestor.parent();
+    }
+
+    false
+}
+
+impl Compound {
+    fn matches(&self, node: &Node) -> bool {
+        let Some(name) = node.name() else {
+            return false;
+        };
+
+        self.name
+            .as_deref()
+            .is_none_or(|expected| expected.eq_ignore_ascii_case(name))
+            && self
+                .id
+                .as_deref()
+                .is_none_or(|id| node.attr("id") == Some(id))
+            && self.classes.iter().all(|class| {
+                node.attr("class")
+                    .is_some_and(|classes| classes.split_whitespace().any(|c| c == class))
+            })
+            && self.attrs.iter().all(|test| test.matches(node))
+    }
+}
+
+impl AttrTest {
+    fn matches(&self, node: &Node) -> bool {
+        let Some(value) = node.attr(&self.name) else {
+            return false;
+        };
+
+        match self.op {
+            AttrOp::Exists => true,
+            AttrOp::Equals => value == self.value,
+            AttrOp::Prefix => value.starts_with(&self.value),
+            AttrOp::Suffix => value.ends_with(&self.value),
+            AttrOp::Contains => value.contains(&self.value),
+        }
+    }
+}
+
+/// Splits `source` at the commas between alternatives, leaving commas in
+/// attribute tests such as `a[href*=","]` alone.
+fn split_alternatives(source: &str) -> Vec<&str> {
+    let mut alternatives = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut in_brackets = false;
+
+    for (i, c) in source.char_indices() {
+        match (quote, c) {
+            (Some(open), _) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if in_brackets => quote = Some(c),
+            (None, '[') => in_brackets = true,
+            (None, ']') => in_brackets = false,
+            (None, ',') if !in_brackets => {
+                alternatives.push(&source[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(&source[start..]);
+
+    alternatives
+}
+
+fn parse_steps(source: &str) -> Result<Vec<(Combinator, Compound)>, String> {
+    let mut steps = Vec::new();
+    let mut combinator = Combinator::Descendant;
+    let mut chars = source.trim().chars().peekable();
+
+    if chars.peek().is_none() {
+        return Err("empty selector".to_owned());
+    }
+
+    while chars.peek().is_some() {
+        steps.push((combinator, parse_compound(&mut chars)?));
+
+        let mut saw_space = false;
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+            saw_space = true;
+        }
+
+        combinator = match chars.peek() {
+            Some('>') => {
+                chars.next();
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+                if chars.peek().is_none() {
+                    return Err("nothing after '>'".to_owned());
+                }
+                Combinator::Child
+            }
+            Some(_) if saw_space => Combinator::Descendant,
+            Some(c) => return Err(format!("unexpected {:?}", c)),
+            None => break,
+        };
+    }
+
+    Ok(steps)
+}
+
+fn parse_compound(chars: &mut Chars<'_>) -> Result<Compound, String> {
+    let mut compound = Compound::default();
+    let mut empty = true;
+
+    if chars.peek() == Some(&'*') {
+        chars.next();
+        empty = false;
+    } else if chars.peek().is_some_and(|c| is_ident(*c)) {
+        compound.name = Some(take_ident(chars));
+        empty = false;
+    }
+
+    loop {
+        match chars.peek() {
+            Some('.') => {
+                chars.next();
+                compound.classes.push(expect_ident(chars, "a class name")?);
+            }
+            Some('#') => {
+                chars.next();
+                compound.id = Some(expect_ident(chars, "an id")?);
+            }
+            Some('[') => {
+                chars.next();
+                compound.attrs.push(parse_attr(chars)?);
+            }
+            Some(':') => {
+                chars.next();
+                chars.next_if_eq(&':');
+                return Err(format!(
+                    "pseudo-classes such as ':{}' are not supported",
+                    take_ident(chars)
+                ));
+            }
+            _ => break,
+        }
+        empty = false;
+    }
+
+    if empty {
+        return Err(match chars.peek() {
+            Some(c) => format!("unexpected {:?}", c),
+            None => "unexpected end".to_owned(),
+        });
+    }
+
+    Ok(compound)
+}
+
+fn parse_attr(chars: &mut Chars<'_>) -> Result<AttrTest, String> {
+    let name = expect_ident(chars, "an attribute name")?;
+
+    let op = match chars.next() {
+        Some(']') => {
+            return Ok(AttrTest {
+                name,
+                op: AttrOp::Exists,
+                value: String::new(),
+            })
+        }
+        Some('=') => AttrOp::Equals,
+        Some(c @ ('^' | '$' | '*')) if chars.next() == Some('=') => match c {
+            '^' => AttrOp::Prefix,
+            '$' => AttrOp::Suffix,
+            _ => AttrOp::Contains,
+        },
+        _ => return Err(format!("malformed test on attribute {:?}", name)),
+    };
+
+    let value = match chars.peek() {
+        Some(&quote @ ('"' | '\'')) => {
+            chars.next();
+            chars.by_ref().take_while(|c| *c != quote).collect()
+        }
+        _ => {
+            let mut value = String::new();
+            while let Some(c) = chars.next_if(|c| *c != ']' && !c.is_whitespace()) {
+                value.push(c);
+            }
+            value
+        }
+    };
+
+    if chars.next() != Some(']') {
+        return Err(format!("unclosed test on attribute {:?}", name));
+    }
+
+    Ok(AttrTest { name, op, value })
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+fn take_ident(chars: &mut Chars<'_>) -> String {
+    let mut ident = String::new();
+    while let Some(c) = chars.next_if(|c| is_ident(*c)) {
+        ident.push(c);
+    }
+    ident
+}
+
+fn expect_ident(chars: &mut Chars<'_>, what: &str) -> Result<String, String> {
+    let ident = take_ident(chars);
+    if ident.is_empty() {
+        return Err(format!("expected {}", what));
+    }
+    Ok(ident)
+}
+
+/// The selectors a `LocalCh` source reads listing pages with. Any selector
+/// left out of a selector file keeps its built-in default.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Selectors {
+    /// One result card per clinic.
+    pub card: Selector,
+    /// The clinic's name, within a card.
+    pub name: Selector,
+    /// The address line, within a card.
+    pub address: Selector,
+    /// Links whose `href` holds a `tel:` phone number, within a card.
+    pub phone: Selector,
+    /// Links to the detail page and the clinic's homepage, within a card.
+    pub link: Selector,
+    /// The result count in the page header.
+    pub results_count: Selector,
+    pub pagination: Selector,
+    /// The link to the next page, within the pagination.
+    pub next_page: Selector,
+}
+
+impl Default for Selectors {
+    fn default() -> Self {
+        let selector = |source| Selector::parse(source).expect("built-in selectors are valid");
+
+        Self {
+            card: selector(".js-entry-card-container"),
+            name: selector("h2.card-info-title"),
+            address: selector(".card-info-address"),
+            phone: selector("a[href^=\"tel:\"]"),
+            link: selector("a[href]"),
+            results_count: selector(".search-header-results-count"),
+            pagination: selector(".pagination"),
+            next_page: selector("a[rel=next]"),
+        }
+    }
+}
+
+impl Selectors {
+    /// Reads selectors from a TOML file such as
+    ///
+    /// ```toml
+    /// card = ".result-card"
+    /// name = ".result-card h3"
+    /// ```
+    ///
+    /// Every selector is checked here, so a typo fails at startup rather
+    /// than as a run of empty pages.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScrapeError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+
+        toml::from_str(&text).map_err(|err| {
+            ScrapeError::Config(format!("invalid selector file {}: {}", path.display(), err))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use select::document::Document;
+
+    use super::*;
+
+    const HTML: &str = r#"
+        <div class="card js-entry-card-container">
+          <h2 class="card-info-title">Klinik Hirslanden</h2>
+          <p><a href="tel:+41443873000">044 387 30 00</a></p>
+          <a rel="next" href="?page=2">Next</a>
+        </div>
+        <h2>Elsewhere</h2>
+    "#;
+
+    fn texts(selector: &str) -> Vec<String> {
+        let document = Document::from(HTML);
+        let selector = Selector::parse(selector).unwrap();
+
+        document
+            .find(&selector)
+            .map(|node| node.text().trim().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn matches_compound_selectors() {
+        assert_eq!(texts("h2.card-info-title"), ["Klinik Hirslanden"]);
+        assert_eq!(texts("a[href^=\"tel:\"]"), ["044 387 30 00"]);
+        assert_eq!(texts("a[rel=next]"), ["Next"]);
+        assert_eq!(texts("h2"), ["Klinik Hirslanden", "Elsewhere"]);
+    }
+
+    #[test]
+    fn matches_combinators() {
+        assert_eq!(texts(".js-entry-card-container h2"), ["Klinik Hirslanden"]);
+        assert_eq!(texts(".card > a"), ["Next"]);
+        assert_eq!(texts(".card > a, p > a").len(), 2);
+    }
+
+    #[test]
+    fn splits_alternatives_only_at_top_level_commas() {
+        assert_eq!(texts("a[href*=\",\"]"), Vec::<String>::new());
+        assert_eq!(
+            texts("a[href*=\",\"], h2.card-info-title"),
+            ["Klinik Hirslanden"]
+        );
+        assert_eq!(texts("a[href^='tel:'], a[rel=next]").len(), 2);
+        assert_eq!(texts("a[href^=tel:]"), ["044 387 30 00"]);
+    }
+
+    #[test]
+    fn rejects_pseudo_classes() {
+        for selector in [
+            "a:first-child",
+            "h2.title::before",
+            "li:nth-child(2)",
+            ":hover",
+        ] {
+            let Err(ScrapeError::Config(err)) = Selector::parse(selector) else {
+                panic!("{:?} was accepted", selector);
+            };
+            assert!(err.contains("pseudo-classes"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_selectors() {
+        for selector in ["", ".", "a[href", "a[href~=x]", "div >", "a, ", "h2!"] {
+            assert!(Selector::parse(selector).is_err(), "{:?}", selector);
+        }
+    }
+}
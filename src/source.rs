@@ -0,0 +1,64 @@
+mod local_ch;
+
+use reqwest::Url;
+use select::document::Document;
+use select::predicate::Name;
+
+use crate::{ListingPage, ScrapeError};
+
+pub use local_ch::LocalCh;
+
+/// Visible characters above which a page counts as having real content.
+const MIN_CONTENT_CHARS: usize = 500;
+
+/// A directory site the crawl engine can page through. The engine takes care
+/// of fetching, retries, rate limiting, robots.txt and concurrency; a source
+/// only knows where its pages live and how to read them.
+pub trait ListingSource: Send + Sync {
+    /// Identifies the search, e.g. to tell checkpoints of different searches
+    /// apart.
+    fn key(&self) -> String;
+
+    /// URL of listing page `page`, counting from 1.
+    fn page_url(&self, page: i32) -> Result<Url, ScrapeError>;
+
+    /// Parses a listing page fetched from `url`, which relative links are
+    /// resolved against. Result cards become clinics via `Clinic::new`.
+    fn parse_page(&self, page: i32, html: &str, url: &Url) -> ListingPage;
+
+    /// The last page number `listing` reveals, if any.
+    fn last_page(&self, listing: &ListingPage) -> Option<i32> {
+        listing.last_page()
+    }
+
+    /// Results shown per listing page, if the site uses a fixed number.
+    fn page_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether a page that yielded no records is genuinely empty rather than
+    /// a page whose markup the parser no longer understands. By default a
+    /// page with a result count is empty when the count is zero or the page
+    /// lies past the last one the count implies; without a page size, only
+    /// page 1 can be checked. A page without a count is empty when it has
+    /// next to no content and no unparsable cards.
+    fn is_genuinely_empty(&self, listing: &ListingPage, html: &str) -> bool {
+        match (listing.total_results, self.page_size()) {
+            (Some(0), _) => true,
+            (Some(total), Some(size)) => listing.page as usize > total.div_ceil(size.max(1)),
+            (Some(_), None) => listing.page > 1,
+            (None, _) => listing.skipped_cards == 0 && visible_text_len(html) < MIN_CONTENT_CHARS,
+        }
+    }
+}
+
+/// Length of the page's body text with runs of whitespace collapsed.
+fn visible_text_len(html: &str) -> usize {
+    let document = Document::from(html);
+    let text = match document.find(Name("body")).next() {
+        Some(body) => body.text(),
+        None => return 0,
+    };
+
+    text.split_whitespace().map(|word| word.len() + 1).sum()
+}
@@ -0,0 +1,155 @@
+use reqwest::Url;
+use select::document::Document;
+use select::node::Node;
+
+use crate::links::{detail_url, external_homepage};
+use crate::{Clinic, ListingPage, ListingSource, PhoneNumber, ScrapeError, SearchQuery, Selectors};
+
+/// Results local.ch shows per listing page.
+const PAGE_SIZE: usize = 10;
+
+/// Search result pages of local.ch, or of a site sharing its markup.
+#[derive(Debug, Clone)]
+pub struct LocalCh {
+    base_url: String,
+    path: String,
+    selectors: Selectors,
+}
+
+impl LocalCh {
+    /// Pages through `{base_url}{path}?page=N`, e.g. a base URL of
+    /// `https://www.local.ch/en/q` and a path of `/Switzerland/clinique`.
+    pub fn new(base_url: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            path: path.into(),
+            selectors: Selectors::default(),
+        }
+    }
+
+    pub fn for_query(site: &str, query: &SearchQuery) -> Self {
+        Self::new(query.base_url(site), query.path())
+    }
+
+    /// Reads pages with `selectors` instead of the built-in ones.
+    pub fn with_selectors(mut self, selectors: Selectors) -> Self {
+        self.selectors = selectors;
+        self
+    }
+
+    fn parse_card(
+        &self,
+        page: i32,
+        card: usize,
+        node: Node,
+        url: &Url,
+    ) -> Result<Clinic, ScrapeError> {
+        let missing = |field| ScrapeError::MissingField { page, card, field };
+
+        let name = node
+            .find(&self.selectors.name)
+            .next()
+            .ok_or_else(|| missing("name"))?;
+
+        let address = node
+            .find(&self.selectors.address)
+            .next()
+            .ok_or_else(|| missing("address"))?;
+
+        let hrefs = node
+            .find(&self.selectors.link)
+            .filter_map(|n| n.attr("href"))
+            .collect::<Vec<_>>();
+
+        let mut phones: Vec<PhoneNumber> = Vec::new();
+        for href in node
+            .find(&self.selectors.phone)
+            .filter_map(|n| n.attr("href"))
+        {
+            match PhoneNumber::parse(href) {
+                Some(phone) if !phones.contains(&phone) => phones.push(phone),
+                Some(_) => {}
+                None => println!("Ignoring unparsable phone number {:?}.", href),
+            }
+        }
+
+        let detail_url = detail_url(url, hrefs.iter().copied());
+        let website = external_homepage(url, hrefs.iter().copied());
+
+        let mut clinic = Clinic::new(name.text().trim(), address.text().trim()).with_phones(phones);
+        if let Some(website) = website {
+            clinic = clinic.with_website(website);
+        }
+        if let Some(detail_url) = detail_url {
+            clinic = clinic.with_detail_url(detail_url);
+        }
+
+        Ok(clinic)
+    }
+}
+
+impl ListingSource for LocalCh {
+    fn key(&self) -> String {
+        format!("{}{}", self.base_url, self.path)
+    }
+
+    fn page_size(&self) -> Option<usize> {
+        Some(PAGE_SIZE)
+    }
+
+    fn page_url(&self, page: i32) -> Result<Url, ScrapeError> {
+        let page_url = format!("{}{}?page={}", self.base_url, self.path, page);
+
+        Url::parse(&page_url)
+            .map_err(|err| ScrapeError::Config(format!("invalid page URL {}: {}", page_url, err)))
+    }
+
+    fn parse_page(&self, page: i32, html: &str, url: &Url) -> ListingPage {
+        let document = Document::from(html);
+
+        let mut skipped_cards = 0;
+        let clinics = document
+            .find(&self.selectors.card)
+            .enumerate()
+            .filter_map(
+                |(card, node)| match self.parse_card(page, card, node, url) {
+                    Ok(clinic) => Some(clinic),
+                    Err(err) => {
+                        println!("Skipping card. Error: {}", err);
+                        skipped_cards += 1;
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let total_results = document
+            .find(&self.selectors.results_count)
+            .next()
+            .and_then(|node| parse_count(&node.text()));
+
+        let has_next = document
+            .find(&self.selectors.pagination)
+            .next()
+            .map(|pagination| pagination.find(&self.selectors.next_page).next().is_some());
+
+        ListingPage {
+            page,
+            clinics,
+            total_results,
+            has_next,
+            skipped_cards,
+        }
+    }
+}
+
+fn parse_count(text: &str) -> Option<usize> {
+    let digits = text
+        .split_whitespace()
+        .find(|word| word.chars().next().is_some_and(|c| c.is_ascii_digit()))?
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>();
+
+    digits.parse().ok()
+}
@@ -0,0 +1,169 @@
+//! A minimal HTTP/1.1 server serving canned responses, so the scraper can be
+//! exercised end to end without network access. Shared by several test
+//! crates, none of which uses every helper.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub const SEARCH_PATH: &str = "/Switzerland/clinique";
+
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("reading {}: {}", path, err))
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    status: u16,
+    body: String,
+    headers: Vec<(String, String)>,
+    delay: Duration,
+}
+
+impl Response {
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            body: body.into(),
+            headers: Vec::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn fixture(name: &str) -> Self {
+        Self::ok(fixture(name))
+    }
+
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            ..Self::ok("")
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+#[derive(Debug, Default)]
+struct Route {
+    /// Served one after another; the last one keeps being served.
+    responses: Vec<Response>,
+    hits: usize,
+}
+
+/// Serves the responses registered per path and query, and 404 for
+/// everything else, robots.txt included.
+#[derive(Debug, Clone)]
+pub struct MockServer {
+    url: String,
+    routes: Arc<Mutex<HashMap<String, Route>>>,
+}
+
+impl MockServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let routes = Arc::new(Mutex::new(HashMap::<String, Route>::new()));
+
+        let server_routes = routes.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return;
+                };
+                tokio::spawn(serve(stream, server_routes.clone()));
+            }
+        });
+
+        Self { url, routes }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Base URL to hand to `Scraper::new` together with `SEARCH_PATH`.
+    pub fn base_url(&self) -> String {
+        format!("{}/en/q", self.url)
+    }
+
+    /// Serves `responses` in turn for listing page `page`.
+    pub fn page(&self, page: i32, responses: Vec<Response>) -> &Self {
+        self.route(&format!("/en/q{}?page={}", SEARCH_PATH, page), responses)
+    }
+
+    pub fn route(&self, path: &str, responses: Vec<Response>) -> &Self {
+        let mut routes = self.routes.lock().unwrap();
+        routes.insert(path.to_owned(), Route { responses, hits: 0 });
+        self
+    }
+
+    /// How often listing page `page` was requested.
+    pub fn hits(&self, page: i32) -> usize {
+        let routes = self.routes.lock().unwrap();
+        routes
+            .get(&format!("/en/q{}?page={}", SEARCH_PATH, page))
+            .map_or(0, |route| route.hits)
+    }
+}
+
+async fn serve(mut stream: TcpStream, routes: Arc<Mutex<HashMap<String, Route>>>) {
+    let mut request = Vec::new();
+    let mut buf = [0; 4096];
+
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let path = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("/")
+        .to_owned();
+
+    let response = {
+        let mut routes = routes.lock().unwrap();
+        match routes.get_mut(&path) {
+            Some(route) => {
+                let index = route.hits.min(route.responses.len() - 1);
+                route.hits += 1;
+                route.responses[index].clone()
+            }
+            None => Response::status(404),
+        }
+    };
+
+    tokio::time::sleep(response.delay).await;
+
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(response.body.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use swiss_info_clinic_scraper::{ClinicDetails, OpeningHours};
+
+use common::fixture;
+
+#[test]
+fn detail_page_is_parsed() {
+    let url = "https://www.local.ch/en/d/zuerich/8032/privatklinik/klinik-hirslanden-AbC123"
+        .parse()
+        .unwrap();
+
+    let details = ClinicDetails::parse(&fixture("detail-page.html"), &url);
+
+    let phones = details
+        .phones
+        .iter()
+        .map(|phone| phone.e164.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(phones, ["+41443873000", "+41800123456"]);
+    assert_eq!(
+        details.fax.map(|fax| fax.e164),
+        Some("+41443873001".to_owned())
+    );
+    assert_eq!(details.email.as_deref(), Some("info@hirslanden.ch"));
+    assert_eq!(
+        details.opening_hours,
+        [
+            OpeningHours {
+                days: "Mon - Fri".to_owned(),
+                hours: "07:00 - 12:00, 13:00 - 19:00".to_owned(),
+            },
+            OpeningHours {
+                days: "Sat".to_owned(),
+                hours: "08:00 - 12:00".to_owned(),
+            },
+        ]
+    );
+    assert_eq!(
+        details.categories,
+        ["Private hospital", "Emergency service"]
+    );
+    assert_eq!(details.languages, ["German", "English"]);
+    assert_eq!(
+        details.description.as_deref(),
+        Some("Private hospital with more than 30 specialist centres.")
+    );
+    assert_eq!(
+        details.social_links,
+        ["https://www.facebook.com/hirslanden"]
+    );
+    assert_eq!(
+        details.website.as_deref(),
+        Some("https://www.hirslanden.ch/")
+    );
+}
+
+#[test]
+fn fax_label_in_a_definition_list_is_found() {
+    let html = r#"<dl>
+        <dt>Phone</dt><dd><a href="tel:+41443873000">044 387 30 00</a></dd>
+        <dt>Fax</dt><dd> <a href="tel:+41443873001">044 387 30 01</a> </dd>
+    </dl>"#;
+    let url = "https://www.local.ch/en/d/x".parse().unwrap();
+
+    let details = ClinicDetails::parse(html, &url);
+
+    assert_eq!(details.phones.len(), 1);
+    assert_eq!(details.phones[0].e164, "+41443873000");
+    assert_eq!(details.fax.unwrap().e164, "+41443873001");
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use swiss_info_clinic_scraper::{JobFile, OutputFormat, ScrapeError};
+
+fn load(file_name: &str, text: &str) -> Result<JobFile, ScrapeError> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(file_name);
+    std::fs::write(&path, text).unwrap();
+
+    JobFile::load(&path)
+}
+
+fn assert_invalid(text: &str, message: &str) {
+    match load("job.toml", text) {
+        Err(ScrapeError::Config(err)) => assert!(err.contains(message), "{}", err),
+        other => panic!("expected a config error, got {:?}", other),
+    }
+}
+
+#[test]
+fn toml_job_file_is_loaded() {
+    let job = load(
+        "job.toml",
+        r#"
+concurrency = 8
+format = "jsonl"
+max_pages = 3
+
+[[queries]]
+name = "clinics-zh"
+query = "clinique"
+canton = "ZH"
+output = "zh.jsonl"
+tags = ["private"]
+
+[[queries]]
+query = "clinique"
+city = "Basel"
+max_pages = 5
+output = "basel.jsonl"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(job.concurrency, 8);
+    assert_eq!(job.format, OutputFormat::JsonLines);
+    assert_eq!(job.queries.len(), 2);
+    assert_eq!(job.queries[0].tags, ["private"]);
+    assert_eq!(job.max_pages(&job.queries[0]), Some(3));
+    assert_eq!(job.max_pages(&job.queries[1]), Some(5));
+
+    let search = job.queries[1].search_query().unwrap();
+    assert_eq!(job.queries[1].name(&search), search.path());
+}
+
+#[test]
+fn yaml_job_file_is_loaded() {
+    let job = load(
+        "job.yml",
+        "
+merge: all.csv
+queries:
+  - query: clinique
+    canton: GE
+  - query: clinique
+    canton: VD
+",
+    )
+    .unwrap();
+
+    assert_eq!(job.concurrency, 4);
+    assert_eq!(job.format, OutputFormat::Csv);
+    assert_eq!(job.merge.as_deref(), Some(Path::new("all.csv")));
+    assert_eq!(job.queries.len(), 2);
+}
+
+#[test]
+fn job_file_with_another_extension_is_rejected() {
+    assert!(matches!(
+        load("job.json", "{}"),
+        Err(ScrapeError::Config(_))
+    ));
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    assert_invalid(
+        r#"
+[[queries]]
+query = "clinique"
+outptu = "out.csv"
+"#,
+        "unknown field",
+    );
+}
+
+#[test]
+fn queries_need_an_output_unless_merged() {
+    assert_invalid(
+        r#"
+[[queries]]
+query = "clinique"
+"#,
+        "has no output",
+    );
+}
+
+#[test]
+fn duplicate_query_names_are_rejected() {
+    assert_invalid(
+        r#"
+merge = "all.csv"
+
+[[queries]]
+name = "clinics"
+query = "clinique"
+
+[[queries]]
+name = "clinics"
+query = "clinique"
+canton = "ZH"
+"#,
+        "duplicate query",
+    );
+}
+
+#[test]
+fn queries_sharing_an_output_are_rejected() {
+    assert_invalid(
+        r#"
+[[queries]]
+query = "clinique"
+canton = "ZH"
+output = "out.csv"
+
+[[queries]]
+query = "clinique"
+canton = "GE"
+output = "./out.csv"
+"#,
+        "another output already uses",
+    );
+}
+
+#[test]
+fn query_writing_to_the_merged_output_is_rejected() {
+    assert_invalid(
+        r#"
+merge = "all.csv"
+
+[[queries]]
+query = "clinique"
+output = "all.csv"
+"#,
+        "another output already uses",
+    );
+}
@@ -0,0 +1,190 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use swiss_info_clinic_scraper::{
+    Clinic, OutputFormat, OutputOptions, PhoneNumber, StreamingWriter,
+};
+
+fn clinic(name: &str) -> Clinic {
+    Clinic::new(name, "Witellikerstrasse 40, 8032 Zürich")
+        .with_phones(vec![PhoneNumber::parse("+41443873000").unwrap()])
+}
+
+fn names(clinics: &[Clinic]) -> Vec<&str> {
+    clinics.iter().map(|clinic| clinic.name()).collect()
+}
+
+const RESUME: OutputOptions = OutputOptions {
+    append: true,
+    atomic: false,
+};
+
+/// Leaves `path` the way a run that crashed while writing its third record
+/// would: two records in full, then `partial`, and for JSON no closing `]`.
+fn write_interrupted(path: &Path, format: OutputFormat, partial: &str) {
+    let mut writer = StreamingWriter::create(path, format, OutputOptions::default()).unwrap();
+    writer
+        .write_page(&[
+            clinic("Klinik Hirslanden"),
+            clinic("Clinique des Grangettes"),
+        ])
+        .unwrap();
+    drop(writer);
+
+    let mut file = OpenOptions::new().append(true).open(path).unwrap();
+    write!(file, "{}", partial).unwrap();
+}
+
+fn assert_resumes(format: OutputFormat, file_name: &str, partial: &str) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(file_name);
+    write_interrupted(&path, format, partial);
+
+    let recovered = format.read_from_path(&path).unwrap();
+    assert_eq!(
+        names(&recovered),
+        ["Klinik Hirslanden", "Clinique des Grangettes"]
+    );
+
+    let mut writer = StreamingWriter::create(&path, format, RESUME).unwrap();
+    writer.write_page(&[clinic("Clinica Sant'Anna")]).unwrap();
+    writer.finish().unwrap();
+
+    let resumed = format.read_from_path(&path).unwrap();
+    assert_eq!(
+        names(&resumed),
+        [
+            "Klinik Hirslanden",
+            "Clinique des Grangettes",
+            "Clinica Sant'Anna"
+        ]
+    );
+}
+
+#[test]
+fn csv_output_resumes_after_a_crash() {
+    assert_resumes(OutputFormat::Csv, "clinics.csv", "Clinica Sant'Anna,\"Via");
+}
+
+#[test]
+fn jsonl_output_resumes_after_a_crash() {
+    assert_resumes(
+        OutputFormat::JsonLines,
+        "clinics.jsonl",
+        "{\"name\":\"Clinica Sant'Anna\",\"addr",
+    );
+}
+
+#[test]
+fn json_output_resumes_after_a_crash() {
+    assert_resumes(
+        OutputFormat::Json,
+        "clinics.json",
+        ",\n  {\"name\":\"Clinica Sant'Anna\",\"addr",
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("clinics.json");
+    write_interrupted(&path, OutputFormat::Json, "");
+    assert!(
+        serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(&path).unwrap())
+            .is_err()
+    );
+    assert_eq!(OutputFormat::Json.read_from_path(&path).unwrap().len(), 2);
+}
+
+#[test]
+fn json_output_rejects_malformed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("clinics.json");
+    std::fs::write(&path, "[\n  {\"name\": 3}\n]\n").unwrap();
+
+    assert!(OutputFormat::Json.read_from_path(&path).is_err());
+    assert!(StreamingWriter::create(&path, OutputFormat::Json, RESUME).is_err());
+}
+
+#[test]
+fn rewrite_replaces_only_the_records_of_this_run() {
+    for (format, file_name) in [
+        (OutputFormat::Csv, "clinics.csv"),
+        (OutputFormat::Json, "clinics.json"),
+        (OutputFormat::JsonLines, "clinics.jsonl"),
+    ] {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(file_name);
+        format
+            .write_to_path(
+                &[clinic("Klinik Hirslanden")],
+                &path,
+                OutputOptions::default(),
+            )
+            .unwrap();
+
+        let mut writer = StreamingWriter::create(&path, format, RESUME).unwrap();
+        writer
+            .write_page(&[
+                clinic("Clinique des Grangettes"),
+                clinic("Clinica Sant'Anna"),
+            ])
+            .unwrap();
+        writer
+            .rewrite(&[
+                clinic("Clinique des Grangettes"),
+                clinic("Universitätsspital Basel"),
+            ])
+            .unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(
+            names(&format.read_from_path(&path).unwrap()),
+            [
+                "Klinik Hirslanden",
+                "Clinique des Grangettes",
+                "Universitätsspital Basel"
+            ],
+            "{:?}",
+            format
+        );
+    }
+}
+
+#[test]
+fn json_output_keeps_missing_fields_as_null() {
+    let value = serde_json::to_value(clinic("Klinik Hirslanden")).unwrap();
+
+    assert_eq!(value["details"], serde_json::Value::Null);
+    assert_eq!(value["source_query"], serde_json::Value::Null);
+    assert_eq!(value["tags"], serde_json::json!([]));
+    let fields = value.as_object().unwrap();
+    for field in ["details", "source_query", "tags"] {
+        assert!(fields.contains_key(field), "{}", field);
+    }
+}
+
+#[test]
+fn resuming_json_output_keeps_earlier_records_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("clinics.json");
+    OutputFormat::Json
+        .write_to_path(
+            &[
+                clinic("Klinik Hirslanden"),
+                clinic("Clinique des Grangettes"),
+            ],
+            &path,
+            OutputOptions::default(),
+        )
+        .unwrap();
+    let before = std::fs::read_to_string(&path).unwrap();
+
+    let writer = StreamingWriter::create(&path, OutputFormat::Json, RESUME).unwrap();
+    let reopened = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(reopened, before.trim_end().trim_end_matches(']').trim_end());
+
+    drop(writer);
+    assert_eq!(
+        names(&OutputFormat::Json.read_from_path(&path).unwrap()),
+        ["Klinik Hirslanden", "Clinique des Grangettes"]
+    );
+}
@@ -0,0 +1,448 @@
+mod common;
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use swiss_info_clinic_scraper::{
+    write_to_csv, Clinic, OutputFormat, PageCache, RetryPolicy, ScrapeError, Scraper, ScraperConfig,
+};
+
+use common::{fixture, MockServer, Response, SEARCH_PATH};
+
+fn scraper(server: &MockServer, max_pages: Option<i32>, max_parallel: usize) -> Scraper {
+    Scraper::new(
+        server.base_url(),
+        SEARCH_PATH.to_owned(),
+        max_pages,
+        max_parallel,
+    )
+    .with_retry_policy(RetryPolicy {
+        base_delay: Duration::from_millis(10),
+        max_delay: Duration::from_millis(50),
+        ..RetryPolicy::default()
+    })
+}
+
+async fn two_page_listing() -> MockServer {
+    let server = MockServer::start().await;
+    server
+        .page(1, vec![Response::fixture("listing-page-1.html")])
+        .page(2, vec![Response::fixture("listing-page-2.html")]);
+    server
+}
+
+fn names(clinics: &[Clinic]) -> Vec<&str> {
+    clinics.iter().map(|clinic| clinic.name()).collect()
+}
+
+#[tokio::test]
+async fn scrape_page_parses_cards_and_skips_malformed_ones() {
+    let server = two_page_listing().await;
+
+    let clinics = scraper(&server, None, 1).scrape_page(1).await.unwrap();
+
+    assert_eq!(
+        names(&clinics),
+        ["Klinik Hirslanden", "Clinique des Grangettes"]
+    );
+
+    let hirslanden = &clinics[0];
+    assert_eq!(hirslanden.street(), Some("Witellikerstrasse"));
+    assert_eq!(hirslanden.house_number(), Some("40"));
+    assert_eq!(hirslanden.postcode(), Some("8032"));
+    assert_eq!(hirslanden.city(), Some("Zürich"));
+    assert_eq!(hirslanden.phone(), Some("+41443873000"));
+    assert_eq!(hirslanden.website(), Some("https://www.hirslanden.ch/"));
+    assert_eq!(
+        hirslanden.detail_url(),
+        Some(
+            format!(
+                "{}/en/d/zuerich/8032/privatklinik/klinik-hirslanden-AbC123",
+                server.url()
+            )
+            .as_str()
+        )
+    );
+
+    let grangettes = &clinics[1];
+    assert_eq!(grangettes.city(), Some("Chêne-Bougeries"));
+    assert_eq!(grangettes.canton(), Some("GE"));
+    assert_eq!(grangettes.phones().len(), 2);
+    assert_eq!(grangettes.website(), None);
+}
+
+#[tokio::test]
+async fn scrape_pages_follows_the_listing_to_its_last_page() {
+    let server = two_page_listing().await;
+
+    let report = scraper(&server, None, 1).scrape_pages().await.unwrap();
+
+    assert_eq!(
+        names(&report.clinics),
+        [
+            "Klinik Hirslanden",
+            "Clinique des Grangettes",
+            "Clinica Sant'Anna",
+            "Universitätsspital Basel",
+        ]
+    );
+    assert_eq!(report.pages_attempted, 2);
+    assert!(report.is_complete());
+    assert_eq!(server.hits(3), 0);
+}
+
+#[tokio::test]
+async fn scrape_pages_parallel_matches_sequential_scrape() {
+    let server = two_page_listing().await;
+
+    let sequential = scraper(&server, None, 1).scrape_pages().await.unwrap();
+    let parallel = scraper(&server, None, 4)
+        .scrape_pages_parallel()
+        .await
+        .unwrap();
+
+    assert_eq!(parallel.clinics, sequential.clinics);
+    assert!(parallel.is_complete());
+}
+
+#[tokio::test]
+async fn empty_page_ends_an_unbounded_crawl() {
+    let server = MockServer::start().await;
+    server
+        .page(
+            1,
+            vec![Response::ok(
+                fixture("listing-page-1.html").replace("4 results", "results"),
+            )],
+        )
+        .page(2, vec![Response::fixture("empty.html")]);
+
+    let report = scraper(&server, None, 1).scrape_pages().await.unwrap();
+
+    assert_eq!(report.clinics.len(), 2);
+    assert!(report.is_complete());
+    assert_eq!(server.hits(3), 0);
+}
+
+#[tokio::test]
+async fn not_found_past_the_end_ends_the_crawl() {
+    let server = MockServer::start().await;
+    server
+        .page(
+            1,
+            vec![Response::ok(
+                fixture("listing-page-1.html").replace("4 results", "results"),
+            )],
+        )
+        .page(2, vec![Response::status(404)]);
+
+    let report = scraper(&server, None, 1).scrape_pages().await.unwrap();
+
+    assert_eq!(report.clinics.len(), 2);
+    assert_eq!(report.pages_attempted, 1);
+    assert!(report.is_complete());
+    assert_eq!(server.hits(2), 1);
+    assert_eq!(server.hits(3), 0);
+}
+
+#[tokio::test]
+async fn rate_limited_page_is_retried() {
+    let server = MockServer::start().await;
+    server.page(
+        1,
+        vec![
+            Response::status(429).with_header("Retry-After", "0"),
+            Response::fixture("listing-page-1.html"),
+        ],
+    );
+
+    let clinics = scraper(&server, Some(1), 1).scrape_page(1).await.unwrap();
+
+    assert_eq!(clinics.len(), 2);
+    assert_eq!(server.hits(1), 2);
+}
+
+#[tokio::test]
+async fn retry_after_beyond_the_max_delay_fails_the_page() {
+    let server = MockServer::start().await;
+    server.page(
+        1,
+        vec![
+            Response::status(429).with_header("Retry-After", "120"),
+            Response::fixture("listing-page-1.html"),
+        ],
+    );
+
+    let err = scraper(&server, Some(1), 1)
+        .scrape_page(1)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            err,
+            ScrapeError::Status {
+                status: StatusCode::TOO_MANY_REQUESTS,
+                retry_after: Some(retry_after),
+                ..
+            } if retry_after == Duration::from_secs(120)
+        ),
+        "{:?}",
+        err
+    );
+    assert_eq!(server.hits(1), 1);
+}
+
+#[tokio::test]
+async fn server_errors_are_reported_as_failed_pages() {
+    let server = MockServer::start().await;
+    server
+        .page(1, vec![Response::fixture("listing-page-1.html")])
+        .page(2, vec![Response::status(500)]);
+
+    let report = scraper(&server, Some(2), 1).scrape_pages().await.unwrap();
+
+    assert_eq!(report.clinics.len(), 2);
+    assert_eq!(report.failed_pages.len(), 1);
+    assert_eq!(report.failed_pages[0].page, 2);
+    assert_eq!(
+        report.failed_pages[0].status,
+        Some(StatusCode::INTERNAL_SERVER_ERROR)
+    );
+    assert_eq!(server.hits(2), RetryPolicy::default().max_attempts as usize);
+
+    let err = scraper(&server, Some(2), 1)
+        .with_max_failure_ratio(0.25)
+        .scrape_pages()
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ScrapeError::TooManyFailures {
+            failed: 1,
+            attempted: 2
+        }
+    ));
+}
+
+#[tokio::test]
+async fn bounded_crawl_attempts_every_page_despite_failures() {
+    let server = MockServer::start().await;
+    for page in 1..=7 {
+        server.page(page, vec![Response::status(500)]);
+    }
+
+    let report = scraper(&server, Some(7), 1)
+        .with_retry_policy(RetryPolicy::none())
+        .scrape_pages()
+        .await
+        .unwrap();
+
+    assert_eq!(report.pages_attempted, 7);
+    assert_eq!(report.failed_pages.len(), 7);
+    assert_eq!(server.hits(7), 1);
+}
+
+#[tokio::test]
+async fn robots_txt_fetch_is_retried_and_not_cached_when_it_fails() {
+    let server = MockServer::start().await;
+    server
+        .route(
+            "/robots.txt",
+            vec![
+                Response::status(503),
+                Response::ok("User-agent: *\nDisallow: /private\n"),
+            ],
+        )
+        .page(1, vec![Response::fixture("listing-page-1.html")]);
+
+    let clinics = scraper(&server, Some(1), 1).scrape_page(1).await.unwrap();
+
+    assert_eq!(clinics.len(), 2);
+    assert_eq!(server.hits(1), 1);
+}
+
+#[tokio::test]
+async fn unavailable_robots_txt_is_not_reported_as_disallowed() {
+    let server = MockServer::start().await;
+    server
+        .route("/robots.txt", vec![Response::status(500)])
+        .page(1, vec![Response::fixture("listing-page-1.html")]);
+
+    let err = scraper(&server, Some(1), 1)
+        .scrape_page(1)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(
+            &err,
+            ScrapeError::RobotsUnavailable { page: 1, source, .. }
+                if source.status() == Some(StatusCode::INTERNAL_SERVER_ERROR)
+        ),
+        "{:?}",
+        err
+    );
+    assert_eq!(server.hits(1), 0);
+}
+
+#[tokio::test]
+async fn slow_responses_time_out() {
+    let server = MockServer::start().await;
+    server.page(
+        1,
+        vec![Response::fixture("listing-page-1.html").delayed(Duration::from_secs(2))],
+    );
+
+    let config = ScraperConfig {
+        timeout: Duration::from_millis(200),
+        ..ScraperConfig::default()
+    };
+    let err = scraper(&server, Some(1), 1)
+        .with_retry_policy(RetryPolicy::none())
+        .with_config(&config)
+        .unwrap()
+        .scrape_page(1)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(&err, ScrapeError::Network { page: 1, source } if source.is_timeout()),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn unrecognized_markup_is_reported_and_saved() {
+    let server = MockServer::start().await;
+    server.page(1, vec![Response::fixture("redesigned.html")]);
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = scraper(&server, Some(1), 1)
+        .with_unparsed_dir(dir.path())
+        .scrape_page(1)
+        .await
+        .unwrap_err();
+
+    let ScrapeError::MarkupChanged {
+        page: 1,
+        saved: Some(saved),
+        ..
+    } = err
+    else {
+        panic!("expected a markup change, got {:?}", err);
+    };
+    assert_eq!(
+        std::fs::read_to_string(saved).unwrap(),
+        fixture("redesigned.html")
+    );
+}
+
+#[tokio::test]
+async fn unrecognized_markup_with_a_result_count_is_reported() {
+    let server = MockServer::start().await;
+    server.page(1, vec![Response::fixture("redesigned-with-count.html")]);
+
+    let report = scraper(&server, None, 1).scrape_pages().await.unwrap();
+
+    assert!(report.clinics.is_empty());
+    let changes = report.markup_changes().collect::<Vec<_>>();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].page, 1);
+}
+
+#[tokio::test]
+async fn result_count_marks_pages_past_the_end_as_empty() {
+    let server = MockServer::start().await;
+    server.page(
+        2,
+        vec![Response::ok(
+            fixture("redesigned-with-count.html").replace("812 results", "4 results"),
+        )],
+    );
+
+    let clinics = scraper(&server, None, 1).scrape_page(2).await.unwrap();
+
+    assert!(clinics.is_empty());
+}
+
+#[tokio::test]
+async fn write_to_csv_writes_every_scraped_clinic() {
+    let server = two_page_listing().await;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("clinics.csv");
+
+    let report = scraper(&server, None, 2)
+        .scrape_pages_parallel()
+        .await
+        .unwrap();
+    write_to_csv(report.clinics.clone(), &path).unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.starts_with("Name,Address,Street,House Number,Postcode,City,Canton,Phone,"));
+    assert_eq!(text.lines().count(), report.clinics.len() + 1);
+
+    let written = OutputFormat::Csv.read_from_path(&path).unwrap();
+    assert_eq!(names(&written), names(&report.clinics));
+    for (written, scraped) in written.iter().zip(&report.clinics) {
+        assert_eq!(written.address(), scraped.address());
+        assert_eq!(written.postcode(), scraped.postcode());
+        assert_eq!(written.phone(), scraped.phone());
+        assert_eq!(written.detail_url(), scraped.detail_url());
+    }
+}
+
+#[tokio::test]
+async fn cached_pages_are_revalidated_and_parsed_offline() {
+    let server = MockServer::start().await;
+    server
+        .page(
+            1,
+            vec![
+                Response::fixture("listing-page-1.html").with_header("ETag", "\"v1\""),
+                Response::status(304),
+            ],
+        )
+        .page(2, vec![Response::fixture("listing-page-2.html")]);
+    let dir = tempfile::tempdir().unwrap();
+    let cache = PageCache::new(dir.path());
+
+    let online = scraper(&server, None, 1).with_cache(cache.clone());
+    let fetched = online.scrape_pages().await.unwrap();
+    let revalidated = online.scrape_pages().await.unwrap();
+    assert_eq!(revalidated.clinics, fetched.clinics);
+    assert_eq!(server.hits(1), 2);
+
+    let url = format!("{}{}?page=1", server.base_url(), SEARCH_PATH);
+    let (meta, _) = cache.get(&url.parse().unwrap()).unwrap().unwrap();
+    assert_eq!(meta.etag.as_deref(), Some("\"v1\""));
+
+    let offline = scraper(&server, None, 2)
+        .offline(cache)
+        .scrape_pages_parallel()
+        .await
+        .unwrap();
+    assert_eq!(offline.clinics, fetched.clinics);
+    assert!(offline.is_complete());
+    assert_eq!(server.hits(1), 2);
+    assert_eq!(server.hits(2), 2);
+}
+
+#[tokio::test]
+async fn offline_scrape_fails_for_uncached_pages() {
+    let server = MockServer::start().await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = scraper(&server, Some(1), 1)
+        .offline(PageCache::new(dir.path()))
+        .scrape_page(1)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, ScrapeError::NotCached { page: 1, .. }),
+        "{:?}",
+        err
+    );
+}